@@ -6,6 +6,24 @@ const { hideBin } = require("yargs/helpers");
 
 const pyaco = require("./index.node");
 
+// Parses a <lang>:<output directory>:<output filename> target
+const parseTarget = (target) => {
+  const langEnd = target.indexOf(":");
+  const directoryEnd = target.lastIndexOf(":");
+
+  if (langEnd === -1 || langEnd === directoryEnd) {
+    throw new Error(
+      `"${target}" is not a valid target, should be formatted as <lang>:<output directory>:<output filename>`
+    );
+  }
+
+  return {
+    lang: target.slice(0, langEnd),
+    outputDirectory: target.slice(langEnd + 1, directoryEnd) || "./",
+    outputFilename: target.slice(directoryEnd + 1),
+  };
+};
+
 yargs(hideBin(process.argv))
   .command(
     "generate",
@@ -25,6 +43,109 @@ yargs(hideBin(process.argv))
           describe:
             "Language used in generated code (elm|purescript|rescript|typescript|typescript-type-1|typescript-type-2)",
         })
+        .option("target", {
+          type: "array",
+          describe:
+            "Additional target formatted as <lang>:<output directory>:<output filename>, can be repeated to generate several languages and files from a single css extraction",
+          default: [],
+        })
+        .option("template", {
+          alias: "t",
+          describe:
+            "Jinja-like template used to generate the code instead of a built-in language, the generated file takes its extension",
+        })
+        .option("source-root", {
+          describe:
+            "Directory Elm and PureScript module names are relative to, defaults to the elm.json or spago config source directories if any",
+        })
+        .option("namespaces", {
+          type: "boolean",
+          describe:
+            "Also group the identifiers into namespaces by utility category (`Spacing.p2`), TypeScript and ReScript only",
+          default: false,
+        })
+        .option("variants", {
+          type: "boolean",
+          describe:
+            "Generate functions for the variants (`md(p2)`, `hover(bgRed500)`) instead of a constant per class with variants, TypeScript, ReScript, and Elm only",
+          default: false,
+        })
+        .option("breakpoints", {
+          type: "boolean",
+          describe:
+            "Also generate a typed `Breakpoint` enum with the pixel widths of the media queries, named after the variants of their classes (`md`)",
+          default: false,
+        })
+        .option("animations", {
+          type: "boolean",
+          describe:
+            "Also generate a typed `AnimationName` listing the `@keyframes` of the stylesheet",
+          default: false,
+        })
+        .option("guards", {
+          type: "boolean",
+          describe:
+            "Also generate runtime guards and parsers checking strings (from a CMS, an API, etc...) against the classes: `isCssClass`, `parseClasses`, and a json `decoder` in Elm",
+          default: false,
+        })
+        .option("tokens", {
+          type: "boolean",
+          describe:
+            "Also generate a module typing the custom properties (`--color-primary`) next to each target, named after the output filename suffixed with `Tokens`",
+          default: false,
+        })
+        .option("include", {
+          type: "array",
+          describe:
+            "Only generate the classes matching this glob (`p-*`), or regex written between slashes (`/^bg-/`), can be repeated, defaults to the `include` patterns of the `[filter]` section of pyaco.toml",
+          default: [],
+        })
+        .option("exclude", {
+          type: "array",
+          describe:
+            "Don't generate the classes matching this glob (`float-*`), or regex written between slashes (`/-(amber|lime)-/`), can be repeated, defaults to the `exclude` patterns of the `[filter]` section of pyaco.toml",
+          default: [],
+        })
+        .option("adapter", {
+          type: "array",
+          describe:
+            "Also generate a module of framework helpers (react|vue|svelte|elm-html|halogen) next to each target supporting it, named after the output filename suffixed with the framework (`CssReact`), can be repeated",
+          default: [],
+        })
+        .option("class-prefix", {
+          describe:
+            "The Tailwind `prefix` of the classes (`tw-`), left out of the identifiers (`tw-p-2` is `p2`) but kept in the class names, defaults to the `class-prefix` of the `[generate]` section of pyaco.toml",
+        })
+        .option("typescript-style", {
+          describe:
+            "How the typescript generator declares the classes (constants|object|const-enum|namespace): a constant per class (`p2`), a single frozen object map (`Css.p2`), a `const enum` (not usable with isolatedModules, as required by esbuild, swc, and Vite), or a namespace, defaults to the `typescript-style` of the `[generate]` section of pyaco.toml, or to constants",
+        })
+        .option("javascript", {
+          describe:
+            "Emit (esm|cjs|both) JavaScript modules (`.mjs`, `.cjs`) with their declaration files (`.d.mts`, `.d.cts`) instead of TypeScript, typescript generators only",
+        })
+        .option("split", {
+          describe:
+            "Split the generated code into several modules grouping the classes by (prefix|category), re-exported by an aggregator module",
+        })
+        .option("max-chunk-classes", {
+          type: "number",
+          describe:
+            "Maximum number of classes per module in split mode, bigger groups are split again",
+          default: 1000,
+        })
+        .option("css-modules", {
+          type: "boolean",
+          describe:
+            "Generate typings next to every CSS Module (`*.module.css`) matching the input glob instead, a `Button.module.css.d.ts` declaration for typescript, or a `ButtonCss.res` binding for rescript, typing the record of the local classes",
+          default: false,
+        })
+        .option("batch", {
+          type: "boolean",
+          describe:
+            "Generate modules for every stylesheet matching the input glob instead, named after the stylesheet, in the same directory relative to the output directory of each target as the stylesheet is to the base directory of the glob, the output filename is ignored",
+          default: false,
+        })
         .option("watch", {
           alias: "w",
           type: "boolean",
@@ -32,19 +153,65 @@ yargs(hideBin(process.argv))
             "Watch for changes in the provided css file and regenarate the code (doesn't work with URL)",
           default: false,
         })
+        .option("check", {
+          type: "boolean",
+          describe:
+            "Don't write anything, exit with an error and print a diff if the generated files are not up to date",
+          default: false,
+        })
+        .option("error-overlay", {
+          describe:
+            "In watch mode, also write the errors of the generation to this file for dev servers to display them, it's removed once the code is generated successfully again",
+        })
         .option("output-directory", {
           alias: "o",
           describe: "Directory for generated code",
           default: "./",
+        })
+        .option("manifest", {
+          describe:
+            "Also write a JSON manifest describing every class (identifier, source, declarations, and media queries) to this path",
+        })
+        .option("json-schema", {
+          describe:
+            "Also write a JSON Schema whose enum lists every class to this path",
+        })
+        .option("tokens-json", {
+          describe:
+            "Also write the custom properties, with their overrides, as JSON design tokens to this path",
         });
     },
     (argv) =>
       pyaco.generate({
         input: argv.input,
         lang: argv.lang,
+        template: argv.template,
+        sourceRoot: argv["source-root"],
+        namespaces: argv.namespaces,
+        variants: argv.variants,
+        breakpoints: argv.breakpoints,
+        animations: argv.animations,
+        guards: argv.guards,
+        tokens: argv.tokens,
+        include: argv.include,
+        exclude: argv.exclude,
+        adapters: argv.adapter,
+        classPrefix: argv["class-prefix"],
+        typescriptStyle: argv["typescript-style"],
+        javascript: argv.javascript,
+        split: argv.split,
+        maxChunkClasses: argv["max-chunk-classes"],
+        cssModules: argv["css-modules"],
+        batch: argv.batch,
         outputDirectory: argv["output-directory"],
         watch: argv.watch,
+        check: argv.check,
+        errorOverlay: argv["error-overlay"],
         outputFilename: argv["output-filename"],
+        targets: argv.target.map(parseTarget),
+        manifest: argv.manifest,
+        jsonSchema: argv["json-schema"],
+        tokensJson: argv["tokens-json"],
       })
   )
   .command(
@@ -60,6 +227,18 @@ yargs(hideBin(process.argv))
           alias: "i",
           describe: "Glob pointing to the files to validate",
         })
+        .option("include", {
+          type: "array",
+          describe:
+            "Only accept the classes matching this glob (`p-*`), or regex written between slashes (`/^bg-/`), can be repeated, defaults to the `include` patterns of the `[filter]` section of pyaco.toml",
+          default: [],
+        })
+        .option("exclude", {
+          type: "array",
+          describe:
+            "Reject the classes matching this glob (`float-*`), or regex written between slashes (`/-(amber|lime)-/`), can be repeated, defaults to the `exclude` patterns of the `[filter]` section of pyaco.toml",
+          default: [],
+        })
         .option("capture-regex", {
           describe:
             "lasses matcher regex, must include a capture containing all the classes",
@@ -75,6 +254,17 @@ yargs(hideBin(process.argv))
           describe:
             "Classes splitter regex, will split the string captured with the `capture_regex` argument and split it into classes",
           default: "\\s+",
+        })
+        .option("animations", {
+          type: "boolean",
+          describe:
+            "Also check the animation names used in inline styles (`animation`, `animation-name`) against the `@keyframes` of the css input",
+          default: false,
+        })
+        .option("style-regex", {
+          describe:
+            "Inline styles matcher regex, must include a capture containing the declarations, used with --animations",
+          default: 'style="([^"]+)"',
         });
     },
     (argv) =>
@@ -82,9 +272,13 @@ yargs(hideBin(process.argv))
         {
           cssInput: argv["css-input"],
           inputGlob: argv["input-glob"],
+          include: argv.include,
+          exclude: argv.exclude,
           captureRegex: argv["capture-regex"],
           maxOpenedFiles: argv["max-opened-files"],
           splitRegex: argv["split-regex"],
+          animations: argv.animations,
+          styleRegex: argv["style-regex"],
         },
         () => {}
       )
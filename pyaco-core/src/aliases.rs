@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+
+/// Named compositions of existing classes (`btn-primary = "px-4 py-2 rounded"`) declared in the
+/// configuration, the members of each alias in order, sorted by alias name
+pub type Aliases = BTreeMap<String, Vec<String>>;
+
+/// Splits the space separated members of the aliases definitions
+pub fn parse_aliases<I, N, M>(definitions: I) -> Aliases
+where
+    I: IntoIterator<Item = (N, M)>,
+    N: Into<String>,
+    M: AsRef<str>,
+{
+    definitions
+        .into_iter()
+        .map(|(name, members)| {
+            (
+                name.into(),
+                members
+                    .as_ref()
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Checks that every alias is made of existing classes, and doesn't shadow one
+pub fn validate_aliases<F>(aliases: &Aliases, is_class: F) -> Result<()>
+where
+    F: Fn(&str) -> bool,
+{
+    for (name, members) in aliases {
+        if is_class(name) {
+            return Err(anyhow!(
+                "alias {} has the name of a class of the stylesheet",
+                name
+            ));
+        }
+
+        if members.is_empty() {
+            return Err(anyhow!("alias {} has no classes", name));
+        }
+
+        let unknown_members = members
+            .iter()
+            .filter(|member| !is_class(member))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        if !unknown_members.is_empty() {
+            return Err(anyhow!(
+                "alias {} is made of unknown classes: {}",
+                name,
+                unknown_members.join(", ")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_members_against_the_classes() {
+        let aliases = parse_aliases(vec![("btn-primary", " px-4  py-2\nrounded ")]);
+
+        assert_eq!(aliases["btn-primary"], ["px-4", "py-2", "rounded"]);
+
+        let classes = ["px-4", "py-2", "rounded"];
+
+        assert!(validate_aliases(&aliases, |class| classes.contains(&class)).is_ok());
+
+        let error = validate_aliases(&aliases, |class| class == "px-4").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "alias btn-primary is made of unknown classes: py-2, rounded"
+        );
+
+        // Every class exists, but so does one named like the alias
+        assert!(validate_aliases(&aliases, |_| true).is_err());
+        assert!(validate_aliases(&parse_aliases(vec![("empty", "")]), |_| false).is_err());
+    }
+}
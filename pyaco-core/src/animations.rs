@@ -0,0 +1,167 @@
+use cssparser::{Parser, ParserInput, Token};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::Classes;
+
+/// Keywords of the `animation` shorthand that can't be animation names
+const ANIMATION_KEYWORDS: &[&str] = &[
+    // Timing functions
+    "linear",
+    "ease",
+    "ease-in",
+    "ease-out",
+    "ease-in-out",
+    "step-start",
+    "step-end",
+    // Iteration count
+    "infinite",
+    // Direction
+    "normal",
+    "reverse",
+    "alternate",
+    "alternate-reverse",
+    // Fill mode
+    "forwards",
+    "backwards",
+    "both",
+    // Play state
+    "running",
+    "paused",
+    // Global values
+    "none",
+    "initial",
+    "inherit",
+    "unset",
+    "revert",
+];
+
+/// A `@keyframes` rule declared in the stylesheet
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Animation {
+    pub name: String,
+    /// The classes whose `animation` or `animation-name` refer to the keyframes (`animate-spin`)
+    pub classes: Vec<String>,
+}
+
+/// All the animations extracted from a css source, sorted by name
+pub type Animations = BTreeMap<String, Animation>;
+
+/// Finds the classes referring to the keyframes
+pub(crate) fn extract_animations(keyframes: Vec<String>, classes: &Classes) -> Animations {
+    let mut animations = keyframes
+        .into_iter()
+        .map(|name| {
+            (
+                name.clone(),
+                Animation {
+                    name,
+                    classes: Vec::new(),
+                },
+            )
+        })
+        .collect::<Animations>();
+
+    for (class, info) in classes {
+        for declaration in info.declarations.iter() {
+            for name in animation_names(&declaration.property, &declaration.value) {
+                if let Some(animation) = animations.get_mut(&name) {
+                    if !animation.classes.contains(class) {
+                        animation.classes.push(class.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    animations
+}
+
+/// The animation names a declaration refers to, for the `animation-name` property
+/// and the `animation` shorthand (vendor prefixes included), empty for any other property
+pub fn animation_names(property: &str, value: &str) -> Vec<String> {
+    let property = match property.strip_prefix('-') {
+        // `-webkit-animation` gives `animation`
+        Some(prefixed) => prefixed
+            .split_once('-')
+            .map_or(prefixed, |(_, property)| property),
+        None => property,
+    };
+
+    let is_shorthand = match property {
+        "animation" => true,
+        "animation-name" => false,
+        _ => return Vec::new(),
+    };
+
+    let mut parser_input = ParserInput::new(value);
+
+    let mut parser = Parser::new(&mut parser_input);
+
+    let mut names = Vec::new();
+
+    while let Ok(token) = parser.next() {
+        let name = match token {
+            Token::Ident(name) if !is_shorthand || !ANIMATION_KEYWORDS.contains(&name.as_ref()) => {
+                name.to_string()
+            }
+            Token::QuotedString(name) => name.to_string(),
+            _ => continue,
+        };
+
+        if name != "none" {
+            names.push(name);
+        }
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClassInfo, Declaration};
+
+    #[test]
+    fn it_finds_animation_names_in_declarations() {
+        assert_eq!(
+            animation_names("animation", "spin 1s linear infinite"),
+            ["spin"]
+        );
+        assert_eq!(
+            animation_names(
+                "-webkit-animation",
+                "ping 1s cubic-bezier(0, 0, 0.2, 1) infinite, fade-in 2s ease-in"
+            ),
+            ["ping", "fade-in"]
+        );
+        assert_eq!(
+            animation_names("animation-name", "bounce, \"wiggle\""),
+            ["bounce", "wiggle"]
+        );
+        assert!(animation_names("animation", "none").is_empty());
+        assert!(animation_names("transition", "opacity 1s ease").is_empty());
+    }
+
+    #[test]
+    fn it_links_keyframes_to_classes() {
+        let mut classes = Classes::new();
+
+        classes.insert(
+            "animate-spin".to_string(),
+            ClassInfo {
+                declarations: vec![Declaration {
+                    property: "animation".to_string(),
+                    value: "spin 1s linear infinite".to_string(),
+                }],
+                ..Default::default()
+            },
+        );
+
+        let animations = extract_animations(vec!["spin".to_string(), "ping".to_string()], &classes);
+
+        assert_eq!(animations.keys().collect::<Vec<_>>(), ["ping", "spin"]);
+        assert!(animations["ping"].classes.is_empty());
+        assert_eq!(animations["spin"].classes, ["animate-spin"]);
+    }
+}
@@ -0,0 +1,224 @@
+use cssparser::{ParseError, Parser, ParserInput, Token};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::Classes;
+
+/// The font size `em` and `rem` widths are converted to pixels with
+const DEFAULT_FONT_SIZE: f64 = 16.0;
+
+/// A media query with width conditions, named after the variant of the classes it contains
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Breakpoint {
+    /// The variant prefix of the classes defined in the media query (`md`)
+    pub name: String,
+    /// The media query, as written in the stylesheet
+    pub query: String,
+    /// In pixels (`min-width`, `width >=`)
+    pub min_width: Option<f64>,
+    /// In pixels (`max-width`, `width <`)
+    pub max_width: Option<f64>,
+}
+
+/// Finds the breakpoints of the stylesheet: the media queries with width conditions, named
+/// after the variant shared by most of the classes they contain, sorted by width.
+/// Media queries containing no class with variants are ignored.
+pub fn extract_breakpoints(classes: &Classes) -> Vec<Breakpoint> {
+    let mut votes: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+
+    for info in classes.values() {
+        for query in info.media.iter() {
+            for variant in info.variants.iter() {
+                *votes.entry(query).or_default().entry(variant).or_default() += 1;
+            }
+        }
+    }
+
+    // Several queries may map to the same variant, the one with the most votes wins
+    let mut breakpoints: BTreeMap<&str, (usize, Breakpoint)> = BTreeMap::new();
+
+    for (query, variants) in votes {
+        let (min_width, max_width) = parse_width_conditions(query);
+
+        if min_width.is_none() && max_width.is_none() {
+            continue;
+        }
+
+        let (name, count) = match variants.into_iter().max_by_key(|(_, count)| *count) {
+            Some(variant) => variant,
+            None => continue,
+        };
+
+        if breakpoints
+            .get(name)
+            .is_some_and(|(best_count, _)| *best_count >= count)
+        {
+            continue;
+        }
+
+        breakpoints.insert(
+            name,
+            (
+                count,
+                Breakpoint {
+                    name: name.to_string(),
+                    query: query.to_string(),
+                    min_width,
+                    max_width,
+                },
+            ),
+        );
+    }
+
+    let mut breakpoints = breakpoints
+        .into_iter()
+        .map(|(_, (_, breakpoint))| breakpoint)
+        .collect::<Vec<_>>();
+
+    breakpoints.sort_by(|a, b| {
+        let width = |breakpoint: &Breakpoint| {
+            (
+                breakpoint.min_width.unwrap_or(0.0),
+                breakpoint.max_width.unwrap_or(f64::INFINITY),
+            )
+        };
+
+        width(a)
+            .partial_cmp(&width(b))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    breakpoints
+}
+
+/// The min and max widths, in pixels, of a media query
+fn parse_width_conditions(query: &str) -> (Option<f64>, Option<f64>) {
+    let mut parser_input = ParserInput::new(query);
+
+    let mut parser = Parser::new(&mut parser_input);
+
+    let mut widths = (None, None);
+
+    collect_width_conditions(&mut parser, &mut widths);
+
+    widths
+}
+
+fn collect_width_conditions<'i>(
+    parser: &mut Parser<'i, '_>,
+    widths: &mut (Option<f64>, Option<f64>),
+) {
+    let mut tokens = Vec::new();
+
+    while let Ok(token) = parser.next() {
+        match token.clone() {
+            Token::ParenthesisBlock => {
+                let _ = parser.parse_nested_block(|parser| -> Result<(), ParseError<'i, ()>> {
+                    collect_width_conditions(parser, widths);
+
+                    Ok(())
+                });
+            }
+            token => tokens.push(token),
+        }
+    }
+
+    match tokens.as_slice() {
+        // `min-width: 640px`
+        [Token::Ident(feature), Token::Colon, value] => match feature.as_ref() {
+            "min-width" => widths.0 = to_pixels(value).or(widths.0),
+            "max-width" => widths.1 = to_pixels(value).or(widths.1),
+            _ => (),
+        },
+        // `width >= 640px`
+        [Token::Ident(feature), Token::Delim(operator), .., value]
+            if feature.as_ref() == "width" =>
+        {
+            match operator {
+                '>' => widths.0 = to_pixels(value).or(widths.0),
+                '<' => widths.1 = to_pixels(value).or(widths.1),
+                _ => (),
+            }
+        }
+        // `640px <= width`
+        [value, Token::Delim(operator), .., Token::Ident(feature)]
+            if feature.as_ref() == "width" =>
+        {
+            match operator {
+                '<' => widths.0 = to_pixels(value).or(widths.0),
+                '>' => widths.1 = to_pixels(value).or(widths.1),
+                _ => (),
+            }
+        }
+        _ => (),
+    }
+}
+
+fn to_pixels(token: &Token) -> Option<f64> {
+    match token {
+        Token::Dimension { value, unit, .. } => match unit.as_ref() {
+            "px" => Some(*value as f64),
+            "em" | "rem" => Some(*value as f64 * DEFAULT_FONT_SIZE),
+            _ => None,
+        },
+        Token::Number { value, .. } if *value == 0.0 => Some(0.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClassInfo;
+
+    #[test]
+    fn it_parses_width_conditions() {
+        assert_eq!(
+            parse_width_conditions("(min-width: 640px)"),
+            (Some(640.0), None)
+        );
+        assert_eq!(
+            parse_width_conditions("screen and (min-width: 48em) and (max-width: 1023px)"),
+            (Some(768.0), Some(1023.0))
+        );
+        assert_eq!(
+            parse_width_conditions("(width >= 40rem)"),
+            (Some(640.0), None)
+        );
+        assert_eq!(
+            parse_width_conditions("(prefers-color-scheme: dark)"),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn it_names_breakpoints_after_the_variants() {
+        let mut classes = Classes::new();
+
+        for (class, variants, media) in [
+            ("md:p-2", vec!["md"], "(min-width: 768px)"),
+            ("md:hover:p-2", vec!["md", "hover"], "(min-width: 768px)"),
+            ("sm:p-2", vec!["sm"], "(min-width: 640px)"),
+            ("dark:p-2", vec!["dark"], "(prefers-color-scheme: dark)"),
+        ] {
+            classes.insert(
+                class.to_string(),
+                ClassInfo {
+                    media: vec![media.to_string()],
+                    variants: variants.into_iter().map(String::from).collect(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let breakpoints = extract_breakpoints(&classes);
+
+        assert_eq!(
+            breakpoints
+                .iter()
+                .map(|breakpoint| (breakpoint.name.as_str(), breakpoint.min_width))
+                .collect::<Vec<_>>(),
+            [("sm", Some(640.0)), ("md", Some(768.0))]
+        );
+    }
+}
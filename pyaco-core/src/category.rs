@@ -0,0 +1,526 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::utils::{strip_class_prefix, utility_name};
+use crate::{ClassInfo, Classes};
+
+/// A utility category, as documented by Tailwind (colors aside)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Category {
+    Layout,
+    FlexboxGrid,
+    Spacing,
+    Sizing,
+    Typography,
+    Color,
+    Backgrounds,
+    Borders,
+    Effects,
+    Filters,
+    Tables,
+    Transitions,
+    Transforms,
+    Interactivity,
+    Svg,
+    Accessibility,
+    Other,
+}
+
+/// CSS properties and the category of the classes declaring them, custom properties
+/// that are not listed are ignored (`--tw-text-opacity`). Patterns work as in [`TAILWIND_PATTERNS`].
+const PROPERTY_PATTERNS: &[(&str, Category)] = &[
+    ("color", Category::Color),
+    ("background-color", Category::Color),
+    ("border-color", Category::Color),
+    ("border-top-color", Category::Color),
+    ("border-right-color", Category::Color),
+    ("border-bottom-color", Category::Color),
+    ("border-left-color", Category::Color),
+    ("outline-color", Category::Color),
+    ("text-decoration-color", Category::Color),
+    ("caret-color", Category::Color),
+    ("accent-color", Category::Color),
+    ("fill", Category::Color),
+    ("stroke", Category::Color),
+    ("--tw-ring-color", Category::Color),
+    ("--tw-ring-offset-color", Category::Color),
+    ("--tw-gradient-from", Category::Color),
+    ("--tw-gradient-to", Category::Color),
+    ("--tw-gradient-stops", Category::Color),
+    ("display", Category::Layout),
+    ("position", Category::Layout),
+    ("top", Category::Layout),
+    ("right", Category::Layout),
+    ("bottom", Category::Layout),
+    ("left", Category::Layout),
+    ("inset*", Category::Layout),
+    ("z-index", Category::Layout),
+    ("float", Category::Layout),
+    ("clear", Category::Layout),
+    ("overflow", Category::Layout),
+    ("overflow-x", Category::Layout),
+    ("overflow-y", Category::Layout),
+    ("overscroll-behavior*", Category::Layout),
+    ("visibility", Category::Layout),
+    ("object-fit", Category::Layout),
+    ("object-position", Category::Layout),
+    ("box-sizing", Category::Layout),
+    ("box-decoration-break", Category::Layout),
+    ("isolation", Category::Layout),
+    ("columns", Category::Layout),
+    ("break-*", Category::Layout),
+    ("aspect-ratio", Category::Layout),
+    ("flex*", Category::FlexboxGrid),
+    ("order", Category::FlexboxGrid),
+    ("grid*", Category::FlexboxGrid),
+    ("gap", Category::FlexboxGrid),
+    ("row-gap", Category::FlexboxGrid),
+    ("column-gap", Category::FlexboxGrid),
+    ("justify-*", Category::FlexboxGrid),
+    ("align-*", Category::FlexboxGrid),
+    ("place-*", Category::FlexboxGrid),
+    ("padding*", Category::Spacing),
+    ("margin*", Category::Spacing),
+    ("--tw-space-*", Category::Spacing),
+    ("width", Category::Sizing),
+    ("min-width", Category::Sizing),
+    ("max-width", Category::Sizing),
+    ("height", Category::Sizing),
+    ("min-height", Category::Sizing),
+    ("max-height", Category::Sizing),
+    ("font*", Category::Typography),
+    ("-webkit-font-smoothing", Category::Typography),
+    ("letter-spacing", Category::Typography),
+    ("line-height", Category::Typography),
+    ("list-style*", Category::Typography),
+    ("text-*", Category::Typography),
+    ("vertical-align", Category::Typography),
+    ("white-space", Category::Typography),
+    ("word-break", Category::Typography),
+    ("overflow-wrap", Category::Typography),
+    ("background-blend-mode", Category::Effects),
+    ("background*", Category::Backgrounds),
+    ("border-collapse", Category::Tables),
+    ("border-spacing", Category::Tables),
+    ("table-layout", Category::Tables),
+    ("caption-side", Category::Tables),
+    ("border*", Category::Borders),
+    ("outline*", Category::Borders),
+    ("--tw-ring-*", Category::Borders),
+    ("box-shadow", Category::Effects),
+    ("--tw-shadow*", Category::Effects),
+    ("opacity", Category::Effects),
+    ("mix-blend-mode", Category::Effects),
+    ("filter", Category::Filters),
+    ("backdrop-filter", Category::Filters),
+    ("transition*", Category::Transitions),
+    ("animation*", Category::Transitions),
+    ("transform*", Category::Transforms),
+    ("--tw-translate-*", Category::Transforms),
+    ("--tw-rotate", Category::Transforms),
+    ("--tw-skew-*", Category::Transforms),
+    ("--tw-scale-*", Category::Transforms),
+    ("cursor", Category::Interactivity),
+    ("pointer-events", Category::Interactivity),
+    ("resize", Category::Interactivity),
+    ("user-select", Category::Interactivity),
+    ("scroll-*", Category::Interactivity),
+    ("touch-action", Category::Interactivity),
+    ("appearance", Category::Interactivity),
+    ("will-change", Category::Interactivity),
+    ("stroke-width", Category::Svg),
+    ("clip", Category::Accessibility),
+];
+
+/// Utility prefixes followed by a color name in the Tailwind color utilities (`text-red-500`)
+const COLOR_UTILITY_PREFIXES: &[&str] = &[
+    "text",
+    "bg",
+    "border",
+    "divide",
+    "ring",
+    "ring-offset",
+    "outline",
+    "fill",
+    "stroke",
+    "from",
+    "via",
+    "to",
+    "placeholder",
+    "decoration",
+    "accent",
+    "caret",
+    "shadow",
+];
+
+/// The Tailwind color names
+const COLOR_NAMES: &[&str] = &[
+    "inherit",
+    "current",
+    "transparent",
+    "black",
+    "white",
+    "slate",
+    "gray",
+    "zinc",
+    "neutral",
+    "stone",
+    "red",
+    "orange",
+    "amber",
+    "yellow",
+    "lime",
+    "green",
+    "emerald",
+    "teal",
+    "cyan",
+    "sky",
+    "blue",
+    "indigo",
+    "violet",
+    "purple",
+    "fuchsia",
+    "pink",
+    "rose",
+];
+
+/// Tailwind utility names and their category, the first matching pattern wins.
+/// Patterns ending with `*` match the utilities starting with what precedes it,
+/// the other ones only match the exact utility name.
+const TAILWIND_PATTERNS: &[(&str, Category)] = &[
+    ("sr-only", Category::Accessibility),
+    ("not-sr-only", Category::Accessibility),
+    ("border-collapse", Category::Tables),
+    ("border-separate", Category::Tables),
+    ("border-spacing-*", Category::Tables),
+    ("table-auto", Category::Tables),
+    ("table-fixed", Category::Tables),
+    ("caption-*", Category::Tables),
+    ("container", Category::Layout),
+    ("block", Category::Layout),
+    ("inline", Category::Layout),
+    ("inline-*", Category::Layout),
+    ("flex", Category::Layout),
+    ("grid", Category::Layout),
+    ("table", Category::Layout),
+    ("table-*", Category::Layout),
+    ("flow-root", Category::Layout),
+    ("contents", Category::Layout),
+    ("list-item", Category::Layout),
+    ("hidden", Category::Layout),
+    ("static", Category::Layout),
+    ("fixed", Category::Layout),
+    ("absolute", Category::Layout),
+    ("relative", Category::Layout),
+    ("sticky", Category::Layout),
+    ("visible", Category::Layout),
+    ("invisible", Category::Layout),
+    ("collapse", Category::Layout),
+    ("isolate", Category::Layout),
+    ("isolation-*", Category::Layout),
+    ("box-*", Category::Layout),
+    ("float-*", Category::Layout),
+    ("clear-*", Category::Layout),
+    ("object-*", Category::Layout),
+    ("overflow-*", Category::Layout),
+    ("overscroll-*", Category::Layout),
+    ("inset-*", Category::Layout),
+    ("top-*", Category::Layout),
+    ("right-*", Category::Layout),
+    ("bottom-*", Category::Layout),
+    ("left-*", Category::Layout),
+    ("z-*", Category::Layout),
+    ("columns-*", Category::Layout),
+    ("break-before-*", Category::Layout),
+    ("break-after-*", Category::Layout),
+    ("break-inside-*", Category::Layout),
+    ("aspect-*", Category::Layout),
+    ("flex-*", Category::FlexboxGrid),
+    ("basis-*", Category::FlexboxGrid),
+    ("grow", Category::FlexboxGrid),
+    ("grow-*", Category::FlexboxGrid),
+    ("shrink", Category::FlexboxGrid),
+    ("shrink-*", Category::FlexboxGrid),
+    ("order-*", Category::FlexboxGrid),
+    ("grid-*", Category::FlexboxGrid),
+    ("col-*", Category::FlexboxGrid),
+    ("row-*", Category::FlexboxGrid),
+    ("auto-cols-*", Category::FlexboxGrid),
+    ("auto-rows-*", Category::FlexboxGrid),
+    ("gap-*", Category::FlexboxGrid),
+    ("justify-*", Category::FlexboxGrid),
+    ("content-*", Category::FlexboxGrid),
+    ("items-*", Category::FlexboxGrid),
+    ("self-*", Category::FlexboxGrid),
+    ("place-*", Category::FlexboxGrid),
+    ("p-*", Category::Spacing),
+    ("px-*", Category::Spacing),
+    ("py-*", Category::Spacing),
+    ("pt-*", Category::Spacing),
+    ("pr-*", Category::Spacing),
+    ("pb-*", Category::Spacing),
+    ("pl-*", Category::Spacing),
+    ("ps-*", Category::Spacing),
+    ("pe-*", Category::Spacing),
+    ("m-*", Category::Spacing),
+    ("mx-*", Category::Spacing),
+    ("my-*", Category::Spacing),
+    ("mt-*", Category::Spacing),
+    ("mr-*", Category::Spacing),
+    ("mb-*", Category::Spacing),
+    ("ml-*", Category::Spacing),
+    ("ms-*", Category::Spacing),
+    ("me-*", Category::Spacing),
+    ("space-*", Category::Spacing),
+    ("w-*", Category::Sizing),
+    ("min-w-*", Category::Sizing),
+    ("max-w-*", Category::Sizing),
+    ("h-*", Category::Sizing),
+    ("min-h-*", Category::Sizing),
+    ("max-h-*", Category::Sizing),
+    ("size-*", Category::Sizing),
+    ("font-*", Category::Typography),
+    ("text-*", Category::Typography),
+    ("antialiased", Category::Typography),
+    ("subpixel-antialiased", Category::Typography),
+    ("italic", Category::Typography),
+    ("not-italic", Category::Typography),
+    ("normal-nums", Category::Typography),
+    ("ordinal", Category::Typography),
+    ("slashed-zero", Category::Typography),
+    ("lining-nums", Category::Typography),
+    ("oldstyle-nums", Category::Typography),
+    ("proportional-nums", Category::Typography),
+    ("tabular-nums", Category::Typography),
+    ("diagonal-fractions", Category::Typography),
+    ("stacked-fractions", Category::Typography),
+    ("tracking-*", Category::Typography),
+    ("leading-*", Category::Typography),
+    ("list-*", Category::Typography),
+    ("placeholder-*", Category::Typography),
+    ("underline", Category::Typography),
+    ("overline", Category::Typography),
+    ("line-through", Category::Typography),
+    ("no-underline", Category::Typography),
+    ("decoration-*", Category::Typography),
+    ("underline-offset-*", Category::Typography),
+    ("uppercase", Category::Typography),
+    ("lowercase", Category::Typography),
+    ("capitalize", Category::Typography),
+    ("normal-case", Category::Typography),
+    ("truncate", Category::Typography),
+    ("indent-*", Category::Typography),
+    ("align-*", Category::Typography),
+    ("whitespace-*", Category::Typography),
+    ("break-*", Category::Typography),
+    ("bg-blend-*", Category::Effects),
+    ("bg-*", Category::Backgrounds),
+    ("from-*", Category::Backgrounds),
+    ("via-*", Category::Backgrounds),
+    ("to-*", Category::Backgrounds),
+    ("rounded", Category::Borders),
+    ("rounded-*", Category::Borders),
+    ("border", Category::Borders),
+    ("border-*", Category::Borders),
+    ("divide-*", Category::Borders),
+    ("outline", Category::Borders),
+    ("outline-*", Category::Borders),
+    ("ring", Category::Borders),
+    ("ring-*", Category::Borders),
+    ("shadow", Category::Effects),
+    ("shadow-*", Category::Effects),
+    ("opacity-*", Category::Effects),
+    ("mix-blend-*", Category::Effects),
+    ("filter", Category::Filters),
+    ("filter-*", Category::Filters),
+    ("blur", Category::Filters),
+    ("blur-*", Category::Filters),
+    ("brightness-*", Category::Filters),
+    ("contrast-*", Category::Filters),
+    ("drop-shadow", Category::Filters),
+    ("drop-shadow-*", Category::Filters),
+    ("grayscale", Category::Filters),
+    ("grayscale-*", Category::Filters),
+    ("hue-rotate-*", Category::Filters),
+    ("invert", Category::Filters),
+    ("invert-*", Category::Filters),
+    ("saturate-*", Category::Filters),
+    ("sepia", Category::Filters),
+    ("sepia-*", Category::Filters),
+    ("backdrop-*", Category::Filters),
+    ("transition", Category::Transitions),
+    ("transition-*", Category::Transitions),
+    ("duration-*", Category::Transitions),
+    ("ease-*", Category::Transitions),
+    ("delay-*", Category::Transitions),
+    ("animate-*", Category::Transitions),
+    ("transform", Category::Transforms),
+    ("transform-*", Category::Transforms),
+    ("scale-*", Category::Transforms),
+    ("rotate-*", Category::Transforms),
+    ("translate-*", Category::Transforms),
+    ("skew-*", Category::Transforms),
+    ("origin-*", Category::Transforms),
+    ("accent-*", Category::Interactivity),
+    ("appearance-*", Category::Interactivity),
+    ("cursor-*", Category::Interactivity),
+    ("caret-*", Category::Interactivity),
+    ("pointer-events-*", Category::Interactivity),
+    ("resize", Category::Interactivity),
+    ("resize-*", Category::Interactivity),
+    ("scroll-*", Category::Interactivity),
+    ("snap-*", Category::Interactivity),
+    ("touch-*", Category::Interactivity),
+    ("select-*", Category::Interactivity),
+    ("will-change-*", Category::Interactivity),
+    ("fill-*", Category::Svg),
+    ("stroke-*", Category::Svg),
+];
+
+fn find_category(patterns: &[(&str, Category)], name: &str) -> Option<Category> {
+    patterns
+        .iter()
+        .find(|(pattern, _)| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == *pattern,
+        })
+        .map(|(_, category)| *category)
+}
+
+impl Category {
+    /// Classifies a class using the first of its declarations that belongs to a category,
+    /// falls back to the Tailwind naming conventions (see [`Category::from_class_name`])
+    pub fn classify(class: &str, info: &ClassInfo) -> Category {
+        info.declarations
+            .iter()
+            .find_map(|declaration| find_category(PROPERTY_PATTERNS, &declaration.property))
+            .unwrap_or_else(|| Category::from_class_name(class))
+    }
+
+    /// Classifies a class using the Tailwind naming conventions, variants are ignored
+    pub fn from_class_name(class: &str) -> Category {
+        let utility = utility_name(class);
+
+        let is_color = COLOR_UTILITY_PREFIXES.iter().any(|prefix| {
+            utility
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_prefix('-'))
+                .is_some_and(|rest| {
+                    let color = rest.split('-').next().unwrap_or(rest);
+
+                    COLOR_NAMES.contains(&color)
+                })
+        });
+
+        if is_color {
+            return Category::Color;
+        }
+
+        find_category(TAILWIND_PATTERNS, utility).unwrap_or(Category::Other)
+    }
+
+    /// The capitalized name of the category, usable as a module name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Layout => "Layout",
+            Category::FlexboxGrid => "FlexboxGrid",
+            Category::Spacing => "Spacing",
+            Category::Sizing => "Sizing",
+            Category::Typography => "Typography",
+            Category::Color => "Color",
+            Category::Backgrounds => "Backgrounds",
+            Category::Borders => "Borders",
+            Category::Effects => "Effects",
+            Category::Filters => "Filters",
+            Category::Tables => "Tables",
+            Category::Transitions => "Transitions",
+            Category::Transforms => "Transforms",
+            Category::Interactivity => "Interactivity",
+            Category::Svg => "Svg",
+            Category::Accessibility => "Accessibility",
+            Category::Other => "Other",
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The class names grouped by category (see [`Category::classify`]), ignoring the Tailwind
+/// `class_prefix` (`tw-`)
+pub fn group_by_category<'a>(
+    classes: &'a Classes,
+    class_prefix: &str,
+) -> BTreeMap<Category, Vec<&'a String>> {
+    let mut groups: BTreeMap<Category, Vec<&String>> = BTreeMap::new();
+
+    for (class, info) in classes {
+        groups
+            .entry(Category::classify(
+                &strip_class_prefix(class, class_prefix),
+                info,
+            ))
+            .or_default()
+            .push(class);
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Declaration;
+
+    #[test]
+    fn it_classifies_tailwind_utilities() {
+        assert_eq!(Category::from_class_name("p-2"), Category::Spacing);
+        assert_eq!(Category::from_class_name("md:-mt-2"), Category::Spacing);
+        assert_eq!(Category::from_class_name("flex"), Category::Layout);
+        assert_eq!(Category::from_class_name("flex-row"), Category::FlexboxGrid);
+        assert_eq!(
+            Category::from_class_name("hover:bg-red-500"),
+            Category::Color
+        );
+        assert_eq!(Category::from_class_name("bg-cover"), Category::Backgrounds);
+        assert_eq!(Category::from_class_name("text-lg"), Category::Typography);
+        assert_eq!(
+            Category::from_class_name("bg-blend-multiply"),
+            Category::Effects
+        );
+        assert_eq!(
+            Category::from_class_name("border-collapse"),
+            Category::Tables
+        );
+        assert_eq!(Category::from_class_name("border-2"), Category::Borders);
+        assert_eq!(Category::from_class_name("card"), Category::Other);
+    }
+
+    #[test]
+    fn it_classifies_classes_using_their_declarations_first() {
+        let info = ClassInfo {
+            declarations: vec![
+                Declaration {
+                    property: "--tw-text-opacity".to_string(),
+                    value: "1".to_string(),
+                },
+                Declaration {
+                    property: "color".to_string(),
+                    value: "rgba(239, 68, 68, var(--tw-text-opacity))".to_string(),
+                },
+            ],
+            media: Vec::new(),
+            ..Default::default()
+        };
+
+        assert_eq!(Category::classify("danger", &info), Category::Color);
+        assert_eq!(
+            Category::classify("p-2", &ClassInfo::default()),
+            Category::Spacing
+        );
+    }
+}
@@ -1,36 +1,162 @@
 use std::collections::HashSet;
+use std::rc::Rc;
 
 use anyhow::Result;
 use cssparser::{
-    AtRuleParser, BasicParseError, BasicParseErrorKind, CowRcStr, ParseError, Parser, ParserState,
-    QualifiedRuleParser, Token,
+    AtRuleParser, BasicParseError, BasicParseErrorKind, CowRcStr, DeclarationListParser,
+    DeclarationParser, ParseError, Parser, ParserState, QualifiedRuleParser, RuleListParser, Token,
 };
+use log::error;
 
-pub struct ClassesParser;
+use crate::deprecations::Annotations;
+use crate::Declaration;
+
+/// A style rule found in the stylesheet
+#[derive(Debug)]
+pub struct Rule {
+    /// The selector list, as written in the stylesheet
+    pub selector: String,
+    /// All the classes found in the rule's selectors
+    pub classes: HashSet<String>,
+    /// The classes the declarations apply to, that is the classes found in
+    /// the rightmost compound selector containing a class (`.bar` in `.foo > .bar`)
+    pub subjects: HashSet<String>,
+    pub declarations: Vec<Declaration>,
+    /// The media queries this rule is nested in, outermost first
+    pub media: Vec<String>,
+    /// The note of the deprecation annotation of the rule, if any
+    pub deprecation: Option<String>,
+}
+
+/// A rule of the stylesheet pyaco extracts something from
+#[derive(Debug)]
+pub enum CssRule {
+    Style(Rule),
+    /// The name of a `@keyframes` rule
+    Keyframes(String),
+}
+
+/// What an @rule contains, as far as pyaco is concerned
+pub enum AtRulePrelude {
+    /// A media query whose rules are parsed
+    Media(String),
+    /// The name of a `@keyframes` rule (`-webkit-keyframes` included)
+    Keyframes(String),
+    /// Any other @rule, ignored
+    Ignored,
+}
+
+pub struct ClassesParser {
+    media: Vec<String>,
+    annotations: Rc<Annotations>,
+    /// Only keep the local classes of a CSS Module, the ones not wrapped in `:global(...)`
+    /// nor following `:global`
+    css_modules: bool,
+}
+
+impl ClassesParser {
+    pub fn new(annotations: Annotations, css_modules: bool) -> Self {
+        Self {
+            media: Vec::new(),
+            annotations: Rc::new(annotations),
+            css_modules,
+        }
+    }
+
+    fn with_media(&self, media: Vec<String>) -> Self {
+        Self {
+            media,
+            annotations: Rc::clone(&self.annotations),
+            css_modules: self.css_modules,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SelectorClasses {
+    selector: String,
+    classes: HashSet<String>,
+    subjects: HashSet<String>,
+    deprecation: Option<String>,
+}
 
 impl<'i> QualifiedRuleParser<'i> for ClassesParser {
-    type Prelude = Option<HashSet<String>>;
-    type QualifiedRule = Option<HashSet<String>>;
+    type Prelude = SelectorClasses;
+    type QualifiedRule = Vec<CssRule>;
     type Error = ();
 
     fn parse_prelude<'t>(
         &mut self,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
-        let mut ret = HashSet::new();
+        let start = input.position();
+
+        let mut ret = SelectorClasses {
+            deprecation: self.annotations.get(&start.byte_index()).cloned(),
+            ..Default::default()
+        };
+
+        // Classes of the compound selector being parsed, and of the last one
+        // that contained at least a class in the current selector
+        let mut compound = HashSet::new();
+        let mut subjects = HashSet::new();
+
+        // Set by `:global` in CSS Modules, until `:local` or the next selector of the list
+        let mut global = false;
 
         loop {
-            match input.next() {
+            match input.next_including_whitespace() {
                 // Match a new potential class
                 Ok(Token::Delim('.')) => {
-                    if let Ok(Token::Ident(ident)) = input.next() {
-                        ret.insert(ident.to_string());
+                    if let Ok(Token::Ident(ident)) = input.next_including_whitespace() {
+                        if !global {
+                            ret.classes.insert(ident.to_string());
+                            compound.insert(ident.to_string());
+                        }
                     } else {
                         // TODO: We should provide a better error here and let the developer know
                         // that the css is probably ill-formatted.
                         return Err(input.new_error(BasicParseErrorKind::QualifiedRuleInvalid));
                     }
                 }
+                // Match a combinator, the next class (if any) belongs to a new compound selector
+                Ok(Token::WhiteSpace(_)) | Ok(Token::Delim('>' | '+' | '~')) => {
+                    if !compound.is_empty() {
+                        subjects = std::mem::take(&mut compound);
+                    }
+                }
+                // Match a new selector in the list
+                Ok(Token::Comma) => {
+                    if !compound.is_empty() {
+                        subjects = std::mem::take(&mut compound);
+                    }
+
+                    ret.subjects.extend(subjects.drain());
+
+                    global = false;
+                }
+                // Match the CSS Modules pseudo classes switching between local and global classes
+                Ok(Token::Colon) if self.css_modules => {
+                    match input.next_including_whitespace().cloned() {
+                        Ok(Token::Ident(mode)) if mode.eq_ignore_ascii_case("global") => {
+                            global = true
+                        }
+                        Ok(Token::Ident(mode)) if mode.eq_ignore_ascii_case("local") => {
+                            global = false
+                        }
+                        Ok(Token::Function(mode)) if mode.eq_ignore_ascii_case("local") => {
+                            let classes = input.parse_nested_block(|input| {
+                                Ok::<_, ParseError<'i, ()>>(nested_classes(input))
+                            })?;
+
+                            ret.classes.extend(classes.iter().cloned());
+                            compound.extend(classes);
+                        }
+                        // The classes of `:global(...)`, like the ones of any other functional
+                        // pseudo class, are nested and ignored
+                        _ => (),
+                    }
+                }
                 // Match any other token and ignore
                 Ok(_) => continue,
                 // Match end of input, break and return found classes if any
@@ -43,7 +169,15 @@ impl<'i> QualifiedRuleParser<'i> for ClassesParser {
             }
         }
 
-        Ok(Some(ret))
+        if !compound.is_empty() {
+            subjects = compound;
+        }
+
+        ret.subjects.extend(subjects);
+
+        ret.selector = input.slice_from(start).trim().to_string();
+
+        Ok(ret)
     }
 
     fn parse_block<'t>(
@@ -52,19 +186,24 @@ impl<'i> QualifiedRuleParser<'i> for ClassesParser {
         _start: &ParserState,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
-        // Consume the block input
-        while input.next().is_ok() {
-            continue;
-        }
+        let declarations = DeclarationListParser::new(input, DeclarationsParser)
+            .filter_map(Result::ok)
+            .collect();
 
-        Ok(prelude)
+        Ok(vec![CssRule::Style(Rule {
+            selector: prelude.selector,
+            classes: prelude.classes,
+            subjects: prelude.subjects,
+            declarations,
+            media: self.media.clone(),
+            deprecation: prelude.deprecation,
+        })])
     }
 }
 
 impl<'i> AtRuleParser<'i> for ClassesParser {
-    // `true` if the @rule body should be parsed, `false` otherwise
-    type Prelude = bool;
-    type AtRule = Option<HashSet<String>>;
+    type Prelude = AtRulePrelude;
+    type AtRule = Vec<CssRule>;
     type Error = ();
 
     #[allow(clippy::type_complexity)]
@@ -73,12 +212,28 @@ impl<'i> AtRuleParser<'i> for ClassesParser {
         name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        let start = input.position();
+
+        let mut keyframes_name = None;
+
         // Consume the rest of the input
-        while input.next().is_ok() {
-            continue;
+        while let Ok(token) = input.next() {
+            if let Token::Ident(value) | Token::QuotedString(value) = token {
+                keyframes_name.get_or_insert_with(|| value.to_string());
+            }
+        }
+
+        if name.as_ref() == "keyframes" || name.ends_with("-keyframes") {
+            return Ok(keyframes_name.map_or(AtRulePrelude::Ignored, AtRulePrelude::Keyframes));
+        }
+
+        if name.as_ref() != "media" {
+            return Ok(AtRulePrelude::Ignored);
         }
 
-        Ok(name.as_ref() == "media")
+        Ok(AtRulePrelude::Media(
+            input.slice_from(start).trim().to_string(),
+        ))
     }
 
     fn parse_block<'t>(
@@ -87,41 +242,39 @@ impl<'i> AtRuleParser<'i> for ClassesParser {
         _start: &ParserState,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::AtRule, ParseError<'i, ()>> {
-        // The @rule should not be parsed any further
-        if !prelude {
-            // Consume the input
-            while input.next().is_ok() {
-                continue;
+        // Only the rules of media queries are parsed any further
+        let query = match prelude {
+            AtRulePrelude::Media(query) => query,
+            prelude => {
+                // Consume the input
+                while input.next().is_ok() {
+                    continue;
+                }
+
+                return Ok(match prelude {
+                    AtRulePrelude::Keyframes(name) => vec![CssRule::Keyframes(name)],
+                    _ => Vec::new(),
+                });
             }
+        };
 
-            return Ok(None);
-        }
+        let mut media = self.media.clone();
 
-        let mut ret = HashSet::new();
+        media.push(query);
 
-        loop {
-            match input.next() {
-                // Match a new potential class
-                Ok(Token::Delim('.')) => {
-                    if let Ok(Token::Ident(ident)) = input.next() {
-                        ret.insert(ident.to_string());
-                    } else {
-                        return Err(input.new_error(BasicParseErrorKind::AtRuleBodyInvalid));
-                    }
-                }
-                // Match any other token and ignore
-                Ok(_) => continue,
-                // Match end of input, break and return found classes if any
-                Err(BasicParseError {
-                    kind: BasicParseErrorKind::EndOfInput,
-                    ..
-                }) => break,
-                // Match any other error and return it
-                Err(error) => return Err(error.into()),
-            }
-        }
+        let rules = RuleListParser::new_for_nested_rule(input, self.with_media(media)).fold(
+            Vec::new(),
+            |mut rules, rules_result| {
+                match rules_result {
+                    Ok(new_rules) => rules.extend(new_rules),
+                    Err(error) => error!("An error occured while parsing the css: {:?}", error),
+                };
+
+                rules
+            },
+        );
 
-        Ok(Some(ret))
+        Ok(rules)
     }
 
     // Simply ignores @rules without blocks, the implementation of this function
@@ -132,6 +285,54 @@ impl<'i> AtRuleParser<'i> for ClassesParser {
         _prelude: Self::Prelude,
         _start: &ParserState,
     ) -> Result<Self::AtRule, ()> {
-        Ok(None)
+        Ok(Vec::new())
     }
 }
+
+/// The classes found in a nested block of a selector (`:local(.foo .bar)`)
+fn nested_classes(input: &mut Parser) -> Vec<String> {
+    let mut classes = Vec::new();
+
+    while let Ok(token) = input.next_including_whitespace() {
+        if let Token::Delim('.') = token {
+            if let Ok(Token::Ident(ident)) = input.next_including_whitespace() {
+                classes.push(ident.to_string());
+            }
+        }
+    }
+
+    classes
+}
+
+/// Collects the declarations of a style rule block, values are kept as written
+struct DeclarationsParser;
+
+impl<'i> DeclarationParser<'i> for DeclarationsParser {
+    type Declaration = Declaration;
+    type Error = ();
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        let start = input.position();
+
+        // Consume the value, `!important` included
+        while input.next().is_ok() {
+            continue;
+        }
+
+        Ok(Declaration {
+            property: name.to_string(),
+            value: input.slice_from(start).trim().to_string(),
+        })
+    }
+}
+
+// Nested @rules in style blocks are ignored
+impl<'i> AtRuleParser<'i> for DeclarationsParser {
+    type Prelude = ();
+    type AtRule = Declaration;
+    type Error = ();
+}
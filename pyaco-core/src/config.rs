@@ -0,0 +1,178 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::{
+    parse_aliases, parse_deprecations, Aliases, ClassFilter, ClassPattern, Deprecations,
+    TypescriptStyle,
+};
+
+/// The configuration file shared by `pyaco generate`, `pyaco validate`, and the `css!` macro
+pub const CONFIG_FILE_NAME: &str = "pyaco.toml";
+
+/// The stylesheet the `css!` macro checks the classes against, a path or an url
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum InputConfig {
+    Simple(String),
+    Path { path: String },
+    Url { url: String },
+}
+
+/// The `[general]` section of the configuration file, required by the `css!` macro only
+#[derive(Debug, Deserialize)]
+pub struct GeneralConfig {
+    pub input: InputConfig,
+}
+
+/// The `[generate]` section of the configuration file, the options of `pyaco generate` it
+/// provides
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct GenerateConfig {
+    pub typescript_style: Option<TypescriptStyle>,
+    pub class_prefix: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawConfig {
+    general: Option<GeneralConfig>,
+    #[serde(default)]
+    generate: GenerateConfig,
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+    #[serde(default)]
+    deprecated: BTreeMap<String, String>,
+    #[serde(default)]
+    filter: ClassFilter,
+}
+
+/// The configuration file, with its `[aliases]` and `[deprecated]` sections parsed,
+/// the options provided on the command line (or from Node) take precedence over it
+#[derive(Debug, Default)]
+pub struct Config {
+    pub general: Option<GeneralConfig>,
+    pub generate: GenerateConfig,
+    pub aliases: Aliases,
+    pub deprecations: Deprecations,
+    pub filter: ClassFilter,
+}
+
+impl Config {
+    /// Reads the configuration file, empty if the file doesn't exist
+    pub fn read<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let content = match read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error.into()),
+        };
+
+        Self::parse(&content)
+            .map_err(|error| anyhow!("invalid configuration file {}: {}", path.display(), error))
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let config: RawConfig = toml::from_str(content)?;
+
+        Ok(Config {
+            general: config.general,
+            generate: config.generate,
+            aliases: parse_aliases(config.aliases),
+            deprecations: parse_deprecations(config.deprecated),
+            filter: config.filter,
+        })
+    }
+
+    /// The filter of the classes, made of the patterns provided on the command line (or from
+    /// Node), or the ones of the `[filter]` section when none is
+    pub fn filter(&self, include: Vec<ClassPattern>, exclude: Vec<ClassPattern>) -> ClassFilter {
+        ClassFilter::new(
+            if include.is_empty() {
+                self.filter.include.clone()
+            } else {
+                include
+            },
+            if exclude.is_empty() {
+                self.filter.exclude.clone()
+            } else {
+                exclude
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_the_sections() {
+        let config = Config::parse(
+            "[general]\ninput = \"styles.css\"\n\n[generate]\ntypescript-style = \"const-enum\"\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.general.unwrap().input,
+            InputConfig::Simple(ref path) if path == "styles.css"
+        ));
+        assert_eq!(
+            config.generate.typescript_style,
+            Some(TypescriptStyle::ConstEnum)
+        );
+
+        let config = Config::parse("[general]\ninput = { url = \"https://cdn/a.css\" }\n").unwrap();
+
+        assert!(matches!(
+            config.general.unwrap().input,
+            InputConfig::Url { .. }
+        ));
+        assert!(config.generate.typescript_style.is_none());
+
+        let config = Config::parse("[generate]\nclass-prefix = \"tw-\"\n").unwrap();
+
+        assert!(config.general.is_none());
+        assert_eq!(config.generate.class_prefix.as_deref(), Some("tw-"));
+
+        assert!(Config::parse("[generate]\ntypescript-style = \"enum\"\n").is_err());
+
+        let config = Config::parse("[aliases]\nbtn-primary = \"px-4 py-2 rounded\"\n").unwrap();
+
+        assert_eq!(config.aliases["btn-primary"], ["px-4", "py-2", "rounded"]);
+
+        let config = Config::parse("[deprecated]\np-2 = \"use .p-3 instead\"\n").unwrap();
+
+        assert!(config.deprecations.contains_key("p-2"));
+
+        let config = Config::parse("[filter]\nexclude = [\"float-*\", \"/^bg-lime-/\"]\n").unwrap();
+
+        assert!(config.filter.include.is_empty());
+        assert!(!config.filter.accepts("bg-lime-500"));
+
+        assert!(Config::parse("[filter]\nexclude = [\"/(/\"]\n").is_err());
+    }
+
+    #[test]
+    fn it_prefers_the_provided_patterns() {
+        let config = Config::parse("[filter]\ninclude = [\"p-*\"]\nexclude = [\"p-2\"]\n").unwrap();
+
+        let filter = config.filter(Vec::new(), Vec::new());
+
+        assert!(filter.accepts("p-4"));
+        assert!(!filter.accepts("p-2"));
+        assert!(!filter.accepts("m-2"));
+
+        let filter = config.filter(vec!["m-*".parse().unwrap()], Vec::new());
+
+        assert!(filter.accepts("m-2"));
+        assert!(!filter.accepts("p-4"));
+    }
+}
@@ -0,0 +1,332 @@
+use anyhow::{anyhow, Result};
+use askama::Template;
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use crate::utils::class_identifier;
+use crate::{extract_classes_from_rules, parse_rules, Classes, GeneratedFile, Lang};
+
+/// The extension of the stylesheets handled as CSS Modules
+pub const CSS_MODULE_EXTENSION: &str = ".module.css";
+
+/// Where the classes a local class `composes:` come from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompositionSource {
+    /// The same module (`composes: base`)
+    Local,
+    /// Global classes, that are not checked (`composes: btn from global`)
+    Global,
+    /// Another module, relative to this one (`composes: base from "./base.module.css"`)
+    File(String),
+}
+
+/// A `composes:` declaration of a local class
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Composition {
+    pub classes: Vec<String>,
+    pub source: CompositionSource,
+}
+
+impl Composition {
+    /// Parses the value of a `composes` declaration: `a b`, `a from global`,
+    /// or `a from "./b.module.css"`
+    pub fn parse(value: &str) -> Result<Self> {
+        let (classes, source) = match value.rsplit_once(" from ") {
+            None => (value, CompositionSource::Local),
+            Some((classes, source)) => {
+                let source = source.trim();
+
+                let source = if source == "global" {
+                    CompositionSource::Global
+                } else {
+                    CompositionSource::File(source.trim_matches(&['"', '\''][..]).to_string())
+                };
+
+                (classes, source)
+            }
+        };
+
+        let classes = classes
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        if classes.is_empty() {
+            return Err(anyhow!("composes: {} doesn't name any class", value));
+        }
+
+        Ok(Composition { classes, source })
+    }
+}
+
+/// A stylesheet whose class names are scoped by the bundler (`Button.module.css`), which exposes
+/// its local classes as a record mapping each of them to its hashed name
+#[derive(Debug)]
+pub struct CssModule {
+    pub path: PathBuf,
+    /// The local classes, the `:global` ones are left out
+    pub classes: Classes,
+    /// The classes each local class composes
+    pub compositions: BTreeMap<String, Vec<Composition>>,
+}
+
+impl CssModule {
+    /// Extracts the local classes of the module, and checks that the classes they compose exist
+    pub fn extract<P>(path: P) -> Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let module = Self::parse(path.into())?;
+
+        module.check_compositions()?;
+
+        Ok(module)
+    }
+
+    fn parse(path: PathBuf) -> Result<Self> {
+        let css_text = read_to_string(&path)
+            .map_err(|error| anyhow!("couldn't read {}: {}", path.display(), error))?;
+
+        let (rules, _) = parse_rules(&css_text, true);
+
+        let classes = extract_classes_from_rules(rules);
+
+        let mut compositions: BTreeMap<String, Vec<Composition>> = BTreeMap::new();
+
+        for (class, info) in classes.iter() {
+            for declaration in info.declarations.iter() {
+                if declaration.property == "composes" {
+                    let composition = Composition::parse(&declaration.value)
+                        .map_err(|error| anyhow!("{}: {}", path.display(), error))?;
+
+                    compositions
+                        .entry(class.clone())
+                        .or_default()
+                        .push(composition);
+                }
+            }
+        }
+
+        Ok(CssModule {
+            path,
+            classes,
+            compositions,
+        })
+    }
+
+    fn check_compositions(&self) -> Result<()> {
+        for (class, compositions) in self.compositions.iter() {
+            for composition in compositions {
+                let composed_classes = match composition.source {
+                    CompositionSource::Global => continue,
+                    CompositionSource::Local => &self.classes,
+                    CompositionSource::File(ref file) => {
+                        let path = self
+                            .path
+                            .parent()
+                            .map_or_else(|| PathBuf::from(file), |parent| parent.join(file));
+
+                        &Self::parse(path)?.classes.clone()
+                    }
+                };
+
+                let unknown_classes = composition
+                    .classes
+                    .iter()
+                    .filter(|composed| !composed_classes.contains_key(*composed))
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+
+                if !unknown_classes.is_empty() {
+                    return Err(anyhow!(
+                        "{}: {} composes unknown classes: {}",
+                        self.path.display(),
+                        class,
+                        unknown_classes.join(", ")
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The name of the stylesheet file, as imported by its sibling typings
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Generates the typings of the module next to it: `Button.module.css.d.ts` for the
+    /// typescript generators, or a `ButtonCss.res` binding for the rescript ones
+    pub fn generate(&self, lang: &Lang) -> Result<GeneratedFile> {
+        let file_name = self.file_name();
+
+        match lang {
+            Lang::Typescript | Lang::TypescriptType1 | Lang::TypescriptType2 => {
+                let code = CssModuleDtsTemplate {
+                    classes: &self.classes,
+                }
+                .render()?;
+
+                Ok(GeneratedFile::new(
+                    format!("{}.d.ts", self.path.to_string_lossy()),
+                    code,
+                ))
+            }
+            Lang::Rescript | Lang::RescriptType => {
+                let code = CssModuleRescriptTemplate {
+                    fields: self
+                        .classes
+                        .keys()
+                        .map(|class| RecordField {
+                            identifier: class_identifier(class),
+                            class,
+                        })
+                        .collect(),
+                    import_path: &format!("./{}", file_name),
+                }
+                .render()?;
+
+                Ok(GeneratedFile::new(
+                    self.path
+                        .with_file_name(format!("{}Css.res", rescript_module_name(&file_name)))
+                        .to_string_lossy(),
+                    code,
+                ))
+            }
+            Lang::Elm | Lang::Purescript => Err(anyhow!(
+                "CSS Modules typings can only be generated for TypeScript and ReScript"
+            )),
+        }
+    }
+}
+
+/// Whether the path is a CSS Module, according to its extension
+pub fn is_css_module<P>(path: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    path.as_ref()
+        .file_name()
+        .is_some_and(|file_name| file_name.to_string_lossy().ends_with(CSS_MODULE_EXTENSION))
+}
+
+/// The capitalized name of the stylesheet, without its extension
+/// (`button.module.css` gives `Button`)
+fn rescript_module_name(file_name: &str) -> String {
+    let name = file_name
+        .strip_suffix(CSS_MODULE_EXTENSION)
+        .unwrap_or(file_name);
+
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+struct RecordField<'a> {
+    identifier: String,
+    class: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "css_module_dts.txt")]
+struct CssModuleDtsTemplate<'a> {
+    classes: &'a Classes,
+}
+
+#[derive(Template)]
+#[template(path = "css_module_rescript.txt")]
+struct CssModuleRescriptTemplate<'a> {
+    fields: Vec<RecordField<'a>>,
+    /// The path of the stylesheet, relative to the binding
+    import_path: &'a str,
+}
+
+mod filters {
+    pub use crate::lang::typescript::filters::{member_doc, string};
+
+    /// A property key of the record, quoted unless it's a valid identifier
+    pub fn key(class: &str) -> askama::Result<String> {
+        let mut chars = class.chars();
+
+        let is_identifier = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+        if is_identifier {
+            Ok(class.to_string())
+        } else {
+            string(class)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+
+    #[test]
+    fn it_extracts_the_local_classes() {
+        let directory =
+            std::env::temp_dir().join(format!("pyaco-css-modules-{}", std::process::id()));
+
+        create_dir_all(&directory).unwrap();
+
+        write(directory.join("base.module.css"), ".base { color: red; }").unwrap();
+
+        let path = directory.join("button.module.css");
+
+        write(
+            &path,
+            r#"
+            .button { composes: base from "./base.module.css"; padding: 0; }
+            .button-primary { composes: button; }
+            :global(.dark) .button, :global .theme .icon { color: white; }
+            :local(.label) { color: black; }
+            "#,
+        )
+        .unwrap();
+
+        let module = CssModule::extract(&path).unwrap();
+
+        assert_eq!(
+            module.classes.keys().collect::<Vec<_>>(),
+            ["button", "button-primary", "label"]
+        );
+        assert_eq!(
+            module.compositions["button"],
+            [Composition {
+                classes: vec!["base".to_string()],
+                source: CompositionSource::File("./base.module.css".to_string()),
+            }]
+        );
+
+        let typings = module.generate(&Lang::Typescript).unwrap();
+
+        assert!(typings.path.ends_with("button.module.css.d.ts"));
+        assert!(typings
+            .code
+            .contains("  readonly \"button-primary\": string;"));
+
+        let binding = module.generate(&Lang::Rescript).unwrap();
+
+        assert!(binding.path.ends_with("ButtonCss.res"));
+        assert!(binding
+            .code
+            .contains("  @as(\"button-primary\") buttonPrimary: string,"));
+        assert!(binding.code.contains("@module(\"./button.module.css\")"));
+
+        write(&path, ".button { composes: missing; }").unwrap();
+
+        assert!(CssModule::extract(&path).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::classes_parser::Rule;
+use crate::utils::custom_property_identifier;
+use crate::GeneratedFile;
+
+/// Selectors whose declarations, outside of any media query, give the default value
+/// of a custom property
+const ROOT_SELECTORS: &[&str] = &[":root", "html", ":host"];
+
+/// Tailwind's own custom properties (`--tw-ring-offset-width`, etc...) are implementation details
+const IGNORED_PREFIXES: &[&str] = &["--tw-"];
+
+/// A custom property (`--color-primary`) declared in the stylesheet, a design token
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CustomProperty {
+    /// The property name, dashes included (`--color-primary`)
+    pub name: String,
+    /// The value declared in `:root` (or `html`, `:host`) outside of any media query,
+    /// the first declared value otherwise
+    pub value: String,
+    /// The other values of the property, in the order of the stylesheet
+    pub overrides: Vec<CustomPropertyOverride>,
+}
+
+/// A value of a custom property that only applies to some selector or media query
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CustomPropertyOverride {
+    pub selector: String,
+    /// The media queries the value is declared in, outermost first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub media: Vec<String>,
+    pub value: String,
+}
+
+/// All the custom properties extracted from a css source, sorted by name
+pub type CustomProperties = BTreeMap<String, CustomProperty>;
+
+/// Finds the custom properties declared in the rules
+pub(crate) fn extract_custom_properties(rules: &[Rule]) -> CustomProperties {
+    let mut values: BTreeMap<&str, Vec<(bool, CustomPropertyOverride)>> = BTreeMap::new();
+
+    for rule in rules {
+        let is_root = rule.media.is_empty()
+            && rule
+                .selector
+                .split(',')
+                .any(|selector| ROOT_SELECTORS.contains(&selector.trim()));
+
+        for declaration in rule.declarations.iter() {
+            let name = declaration.property.as_str();
+
+            if !name.starts_with("--")
+                || IGNORED_PREFIXES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            {
+                continue;
+            }
+
+            values.entry(name).or_default().push((
+                is_root,
+                CustomPropertyOverride {
+                    selector: rule.selector.clone(),
+                    media: rule.media.clone(),
+                    value: declaration.value.clone(),
+                },
+            ));
+        }
+    }
+
+    values
+        .into_iter()
+        .map(|(name, mut values)| {
+            let default_index = values.iter().position(|(is_root, _)| *is_root).unwrap_or(0);
+
+            let (_, default) = values.remove(default_index);
+
+            let custom_property = CustomProperty {
+                name: name.to_string(),
+                value: default.value,
+                overrides: values.into_iter().map(|(_, value)| value).collect(),
+            };
+
+            (name.to_string(), custom_property)
+        })
+        .collect()
+}
+
+impl CustomProperty {
+    /// The identifier used for the property in the generated code (`colorPrimary`)
+    pub fn identifier(&self) -> String {
+        custom_property_identifier(&self.name)
+    }
+
+    /// The `var()` function referencing the property (`var(--color-primary)`)
+    pub fn var(&self) -> String {
+        format!("var({})", self.name)
+    }
+}
+
+#[derive(Serialize)]
+struct DesignToken<'a> {
+    name: &'a str,
+    identifier: String,
+    var: String,
+    value: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    overrides: &'a Vec<CustomPropertyOverride>,
+}
+
+/// A JSON export of the custom properties, meant to be consumed by design tools
+/// or token pipelines that can't link against pyaco
+#[derive(Serialize)]
+pub struct DesignTokens<'a> {
+    source: &'a str,
+    tokens: Vec<DesignToken<'a>>,
+}
+
+impl<'a> DesignTokens<'a> {
+    pub fn new(source: &'a str, custom_properties: &'a CustomProperties) -> Self {
+        let tokens = custom_properties
+            .values()
+            .map(|custom_property| DesignToken {
+                name: &custom_property.name,
+                identifier: custom_property.identifier(),
+                var: custom_property.var(),
+                value: &custom_property.value,
+                overrides: &custom_property.overrides,
+            })
+            .collect();
+
+        DesignTokens { source, tokens }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn generate<P>(&self, path: P) -> Result<GeneratedFile>
+    where
+        P: Into<String>,
+    {
+        Ok(GeneratedFile::new(path, self.to_json()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract_stylesheet_from_text;
+
+    #[test]
+    fn it_extracts_custom_properties_with_their_overrides() {
+        let stylesheet = extract_stylesheet_from_text(
+            ".dark { --color-primary: #1e40af } \
+             :root { --color-primary: #3b82f6; --font-sans: \"Inter\", sans-serif } \
+             @media (prefers-color-scheme: dark) { :root { --color-primary: #1e3a8a } } \
+             .p-2 { padding: 0.5rem; --tw-ring-inset: var(--tw-empty,/*!*/ /*!*/) } \
+             .card { --space-card: 1rem }",
+        )
+        .unwrap();
+
+        let custom_properties = stylesheet.custom_properties;
+
+        assert_eq!(
+            custom_properties.keys().collect::<Vec<_>>(),
+            ["--color-primary", "--font-sans", "--space-card"]
+        );
+
+        let color_primary = &custom_properties["--color-primary"];
+
+        assert_eq!(color_primary.value, "#3b82f6");
+        assert_eq!(color_primary.identifier(), "colorPrimary");
+        assert_eq!(color_primary.var(), "var(--color-primary)");
+        assert_eq!(
+            color_primary.overrides,
+            [
+                CustomPropertyOverride {
+                    selector: ".dark".to_string(),
+                    media: Vec::new(),
+                    value: "#1e40af".to_string(),
+                },
+                CustomPropertyOverride {
+                    selector: ":root".to_string(),
+                    media: vec!["(prefers-color-scheme: dark)".to_string()],
+                    value: "#1e3a8a".to_string(),
+                }
+            ]
+        );
+
+        assert_eq!(
+            custom_properties["--font-sans"].value,
+            "\"Inter\", sans-serif"
+        );
+
+        // Without a root value the first one is the default
+        assert_eq!(custom_properties["--space-card"].value, "1rem");
+        assert!(custom_properties["--space-card"].overrides.is_empty());
+    }
+
+    #[test]
+    fn it_exports_design_tokens() {
+        let stylesheet = extract_stylesheet_from_text(
+            ":root { --space-4: 1rem } .p-4 { padding: var(--space-4) }",
+        )
+        .unwrap();
+
+        let tokens: serde_json::Value = serde_json::from_str(
+            &DesignTokens::new("styles.css", &stylesheet.custom_properties)
+                .to_json()
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tokens["tokens"][0]["name"], "--space-4");
+        assert_eq!(tokens["tokens"][0]["identifier"], "space4");
+        assert_eq!(tokens["tokens"][0]["var"], "var(--space-4)");
+        assert_eq!(tokens["tokens"][0]["value"], "1rem");
+        assert!(tokens["tokens"][0].get("overrides").is_none());
+    }
+}
@@ -0,0 +1,245 @@
+use cssparser::{ParseError, Parser, ParserInput, Token};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+
+use crate::Classes;
+
+/// The annotation marking the rule following it, or the rule it's written in, as deprecated
+/// (`/* @pyaco-deprecated use .p-3 instead */`)
+pub const DEPRECATION_ANNOTATION: &str = "@pyaco-deprecated";
+
+/// Why a class shouldn't be used anymore
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    /// The note following the annotation, or given in the configuration, possibly empty
+    pub note: String,
+    /// The first class the note refers to (`p-3` in `use .p-3 instead`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+}
+
+impl Deprecation {
+    pub fn new<N>(note: N) -> Self
+    where
+        N: Into<String>,
+    {
+        let note = note.into();
+
+        let replacement = note
+            .split_whitespace()
+            .filter_map(|word| word.strip_prefix('.'))
+            .map(|class| unescape(class.trim_end_matches(&['.', ',', ';', ':', ')'][..])))
+            .find(|class| !class.is_empty());
+
+        Deprecation { note, replacement }
+    }
+
+    /// A one line description of the deprecation of the class, meant for warnings
+    pub fn describe(&self, class: &str) -> String {
+        if self.note.is_empty() {
+            format!("{} is deprecated", class)
+        } else {
+            format!("{} is deprecated: {}", class, self.note)
+        }
+    }
+}
+
+impl Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.note)
+    }
+}
+
+/// The deprecated classes, sorted by name
+pub type Deprecations = BTreeMap<String, Deprecation>;
+
+/// Reads the notes of the deprecations declared in the configuration
+pub fn parse_deprecations<I, N, M>(definitions: I) -> Deprecations
+where
+    I: IntoIterator<Item = (N, M)>,
+    N: Into<String>,
+    M: AsRef<str>,
+{
+    definitions
+        .into_iter()
+        .map(|(class, note)| (class.into(), Deprecation::new(note.as_ref().trim())))
+        .collect()
+}
+
+/// Marks the classes as deprecated, the configured deprecations take precedence over
+/// the annotations found in the css. Unknown classes are ignored.
+pub fn apply_deprecations(classes: &mut Classes, deprecations: &Deprecations) {
+    for (class, deprecation) in deprecations {
+        if let Some(info) = classes.get_mut(class) {
+            info.deprecated = Some(deprecation.clone());
+        }
+    }
+}
+
+/// The deprecations of the classes, annotated in the css or applied from the configuration
+pub fn deprecated_classes(classes: &Classes) -> Deprecations {
+    classes
+        .iter()
+        .filter_map(|(class, info)| {
+            info.deprecated
+                .as_ref()
+                .map(|deprecation| (class.clone(), deprecation.clone()))
+        })
+        .collect()
+}
+
+/// Deprecation notes, keyed by the byte index the prelude of the rule they apply to starts at
+pub(crate) type Annotations = HashMap<usize, String>;
+
+/// Finds the deprecation annotations of a stylesheet. The parser discards comments, so
+/// they are looked for ahead of the parsing and matched against the rules by position.
+pub(crate) fn find_annotations(css: &str) -> Annotations {
+    let mut annotations = Annotations::new();
+
+    // Most stylesheets don't use annotations at all
+    if !css.contains(DEPRECATION_ANNOTATION) {
+        return annotations;
+    }
+
+    let mut parser_input = ParserInput::new(css);
+
+    collect_annotations(&mut Parser::new(&mut parser_input), None, &mut annotations);
+
+    annotations
+}
+
+/// An annotation applies to the next rule, and to the rule of the block it's written in
+fn collect_annotations(
+    input: &mut Parser,
+    block_rule: Option<usize>,
+    annotations: &mut Annotations,
+) {
+    let mut pending_note = None;
+    let mut rule_start = None;
+
+    loop {
+        let position = input.position().byte_index();
+
+        let token = match input.next_including_whitespace_and_comments() {
+            Ok(token) => token.clone(),
+            Err(_) => break,
+        };
+
+        match token {
+            Token::Comment(comment) => {
+                if let Some(note) = annotation_note(comment) {
+                    if let Some(block_rule) = block_rule {
+                        annotations.insert(block_rule, note.clone());
+                    }
+
+                    pending_note = Some(note);
+                }
+            }
+            Token::WhiteSpace(_) => (),
+            Token::CurlyBracketBlock => {
+                let _ = input.parse_nested_block(|input| {
+                    collect_annotations(input, rule_start, annotations);
+
+                    Ok::<_, ParseError<()>>(())
+                });
+
+                rule_start = None;
+            }
+            Token::Semicolon => rule_start = None,
+            _ => {
+                if rule_start.is_none() {
+                    rule_start = Some(position);
+
+                    if let Some(note) = pending_note.take() {
+                        annotations.insert(position, note);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The note of a deprecation annotation, `None` if the comment isn't one
+fn annotation_note(comment: &str) -> Option<String> {
+    let note = comment
+        .trim_start_matches(|c: char| c == '*' || c.is_whitespace())
+        .strip_prefix(DEPRECATION_ANNOTATION)?;
+
+    if note.starts_with(|c: char| !c.is_whitespace()) {
+        return None;
+    }
+
+    Some(
+        note.split_whitespace()
+            .filter(|word| *word != "*")
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Removes the css escapes of a class name (`w-1\/2` gives `w-1/2`)
+fn unescape(class: &str) -> String {
+    let mut unescaped = String::with_capacity(class.len());
+    let mut chars = class.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract_stylesheet_from_text;
+
+    #[test]
+    fn it_finds_the_replacement_in_the_note() {
+        let deprecation = Deprecation::new("use .w-1\\/2 instead.");
+
+        assert_eq!(deprecation.replacement, Some("w-1/2".to_string()));
+        assert_eq!(Deprecation::new("obsolete").replacement, None);
+        assert_eq!(
+            Deprecation::new("").describe("p-2"),
+            "p-2 is deprecated".to_string()
+        );
+    }
+
+    #[test]
+    fn it_reads_annotations_before_and_inside_rules() {
+        let css = r#"
+            /* @pyaco-deprecated use .p-3 instead */
+            .p-2 { padding: 0.5rem; }
+
+            .p-3 { padding: 0.75rem; }
+
+            @media (min-width: 768px) {
+                /* Not an annotation: @pyaco-deprecated */
+                .md\:p-2 {
+                    /** @pyaco-deprecated */
+                    padding: 0.5rem;
+                }
+
+                .md\:p-3 { padding: 0.75rem; }
+            }
+
+            .old:hover { /* @pyaco-deprecated-soon */ color: red; }
+        "#;
+
+        let classes = extract_stylesheet_from_text(css).unwrap().classes;
+
+        assert_eq!(
+            classes["p-2"].deprecated,
+            Some(Deprecation::new("use .p-3 instead"))
+        );
+        assert_eq!(classes["md:p-2"].deprecated, Some(Deprecation::new("")));
+        assert_eq!(classes["p-3"].deprecated, None);
+        assert_eq!(classes["md:p-3"].deprecated, None);
+        assert_eq!(classes["old"].deprecated, None);
+    }
+}
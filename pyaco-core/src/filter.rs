@@ -0,0 +1,125 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::str::FromStr;
+
+use crate::Classes;
+
+/// A pattern class names are matched against: a glob (`float-*`), or a regex written between
+/// slashes (`/^bg-(amber|lime)-\d+$/`)
+#[derive(Clone, Debug)]
+pub enum ClassPattern {
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+impl FromStr for ClassPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+            Some(regex) => Regex::new(regex)
+                .map(ClassPattern::Regex)
+                .map_err(|error| format!("\"{}\" is not a valid regex: {}", s, error)),
+            None => glob::Pattern::new(s)
+                .map(ClassPattern::Glob)
+                .map_err(|error| format!("\"{}\" is not a valid glob: {}", s, error)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ClassPattern {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl ClassPattern {
+    /// Globs must match the whole class name, while regexes can match a part of it
+    pub fn matches(&self, class: &str) -> bool {
+        match self {
+            ClassPattern::Glob(pattern) => pattern.matches(class),
+            ClassPattern::Regex(regex) => regex.is_match(class),
+        }
+    }
+}
+
+/// Selects the extracted classes that are kept: the ones matching any of the `include`
+/// patterns (every class if there is none), and none of the `exclude` patterns
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClassFilter {
+    #[serde(default)]
+    pub include: Vec<ClassPattern>,
+    #[serde(default)]
+    pub exclude: Vec<ClassPattern>,
+}
+
+impl ClassFilter {
+    pub fn new(include: Vec<ClassPattern>, exclude: Vec<ClassPattern>) -> Self {
+        ClassFilter { include, exclude }
+    }
+
+    /// Whether every class is kept
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn accepts(&self, class: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches(class)))
+            && !self.exclude.iter().any(|pattern| pattern.matches(class))
+    }
+
+    /// Removes the classes that are not accepted, it's an error to filter them all out
+    pub fn apply(&self, classes: &mut Classes) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        classes.retain(|class, _| self.accepts(class));
+
+        if classes.is_empty() {
+            return Err(anyhow!(
+                "every css class was filtered out, check the include and exclude patterns"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> Vec<ClassPattern> {
+        patterns
+            .iter()
+            .map(|pattern| pattern.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn it_matches_globs_and_regexes() {
+        let filter = ClassFilter::new(
+            patterns(&["p-*", "/^bg-/", "w-1/2"]),
+            patterns(&["p-[13]", "/-(amber|lime)-/"]),
+        );
+
+        assert!(filter.accepts("p-2"));
+        assert!(filter.accepts("bg-red-500"));
+        assert!(filter.accepts("w-1/2"));
+        assert!(!filter.accepts("p-1"));
+        assert!(!filter.accepts("bg-lime-500"));
+        assert!(!filter.accepts("hover:bg-red-500"));
+        assert!(!filter.accepts("float-left"));
+
+        assert!(ClassFilter::default().accepts("float-left"));
+        assert!("/(/".parse::<ClassPattern>().is_err());
+    }
+}
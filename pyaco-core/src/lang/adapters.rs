@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Result};
+use askama::Template;
+use std::str::FromStr;
+
+use super::{generate_module_name, render_file, validate_module_name, Lang, TemplateOptions};
+use crate::GeneratedFile;
+
+/// The frameworks helpers can be generated for, alongside the classes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framework {
+    /// React `className` (TypeScript), and ReScript-React (ReScript)
+    React,
+    /// Vue `:class` bindings (TypeScript)
+    Vue,
+    /// Svelte `class` attributes and a typed `class:` action (TypeScript)
+    Svelte,
+    /// elm/html `classList` (Elm)
+    ElmHtml,
+    /// Halogen `HP.classes` (PureScript)
+    Halogen,
+}
+
+impl FromStr for Framework {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "react" => Ok(Framework::React),
+            "vue" => Ok(Framework::Vue),
+            "svelte" => Ok(Framework::Svelte),
+            "elm-html" => Ok(Framework::ElmHtml),
+            "halogen" => Ok(Framework::Halogen),
+            unknown => Err(format!(
+                "\"{}\" is not a valid framework, should be one of (react|vue|svelte|elm-html|halogen)",
+                unknown
+            )),
+        }
+    }
+}
+
+impl Framework {
+    /// Whether helpers can be generated for this framework in the lang
+    pub fn supports(self, lang: &Lang) -> bool {
+        match self {
+            Framework::React => !matches!(lang, Lang::Elm | Lang::Purescript),
+            Framework::Vue | Framework::Svelte => matches!(
+                lang,
+                Lang::Typescript | Lang::TypescriptType1 | Lang::TypescriptType2
+            ),
+            Framework::ElmHtml => matches!(lang, Lang::Elm),
+            Framework::Halogen => matches!(lang, Lang::Purescript),
+        }
+    }
+
+    /// Appended to the output filename to name the adapter module
+    fn suffix(self) -> &'static str {
+        match self {
+            Framework::React => "React",
+            Framework::Vue => "Vue",
+            Framework::Svelte => "Svelte",
+            Framework::ElmHtml => "Html",
+            Framework::Halogen => "Halogen",
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "typescript_adapter.txt")]
+struct TypescriptAdapterTemplate<'a> {
+    framework: Framework,
+    classes_module: &'a str,
+    /// The classes of the `typescript` generator are opaque and need `join` to be read
+    opaque: bool,
+}
+
+#[derive(Template)]
+#[template(path = "rescript_adapter.txt")]
+struct RescriptAdapterTemplate {
+    classes_module: String,
+}
+
+#[derive(Template)]
+#[template(path = "elm_adapter.txt")]
+struct ElmAdapterTemplate {
+    module_name: String,
+    classes_module_name: String,
+}
+
+#[derive(Template)]
+#[template(path = "purescript_adapter.txt")]
+struct PurescriptAdapterTemplate {
+    module_name: String,
+    classes_module_name: String,
+}
+
+/// Generates a module of helpers binding the classes to a framework (conditional classes typed
+/// against `CssClass`, etc...) next to the target, named after the output filename suffixed with
+/// the framework (`CssReact`)
+pub fn generate_adapter(
+    lang: &Lang,
+    options: &TemplateOptions,
+    framework: Framework,
+) -> Result<Vec<GeneratedFile>> {
+    if !framework.supports(lang) {
+        return Err(anyhow!(
+            "no {:?} helpers can be generated for {:?}",
+            framework,
+            lang
+        ));
+    }
+
+    let adapter_options = TemplateOptions {
+        output_filename: format!("{}{}", options.output_filename, framework.suffix()),
+        ..options.clone()
+    };
+
+    let file = match lang {
+        Lang::Elm => render_file(
+            &adapter_options,
+            &ElmAdapterTemplate {
+                module_name: module_name(&adapter_options)?,
+                classes_module_name: module_name(options)?,
+            },
+            "elm",
+        )?,
+        Lang::Purescript => render_file(
+            &adapter_options,
+            &PurescriptAdapterTemplate {
+                module_name: module_name(&adapter_options)?,
+                classes_module_name: module_name(options)?,
+            },
+            "purs",
+        )?,
+        Lang::Rescript | Lang::RescriptType => {
+            // ReScript modules are named after their capitalized filename
+            let mut chars = options.output_filename.chars();
+
+            let classes_module = chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default();
+
+            render_file(
+                &adapter_options,
+                &RescriptAdapterTemplate { classes_module },
+                "res",
+            )?
+        }
+        Lang::Typescript | Lang::TypescriptType1 | Lang::TypescriptType2 => render_file(
+            &adapter_options,
+            &TypescriptAdapterTemplate {
+                framework,
+                classes_module: &options.output_filename,
+                opaque: matches!(lang, Lang::Typescript),
+            },
+            "ts",
+        )?,
+    };
+
+    Ok(vec![file])
+}
+
+fn module_name(options: &TemplateOptions) -> Result<String> {
+    let module_name = generate_module_name(options)?;
+
+    validate_module_name(&module_name)?;
+
+    Ok(module_name.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_generates_helpers_next_to_the_target() {
+        let options = TemplateOptions {
+            source_root: Some("src".into()),
+            ..TemplateOptions::new("src/Styles", "Css")
+        };
+
+        let files = generate_adapter(&Lang::Elm, &options, Framework::ElmHtml).unwrap();
+
+        assert_eq!(files[0].path, "src/Styles/CssHtml.elm");
+        assert!(files[0].code.contains("module Styles.CssHtml exposing"));
+        assert!(files[0]
+            .code
+            .contains("import Styles.Css exposing (CssClass, join)"));
+
+        let files = generate_adapter(&Lang::Typescript, &options, Framework::React).unwrap();
+
+        assert_eq!(files[0].path, "src/Styles/CssReact.ts");
+        assert!(files[0].code.contains("import { join } from \"./Css\";"));
+
+        let files = generate_adapter(&Lang::TypescriptType1, &options, Framework::Svelte).unwrap();
+
+        assert!(!files[0].code.contains("import { join }"));
+        assert!(files[0].code.contains(".join(\" \")\n      .split(/\\s+/)"));
+
+        assert!(generate_adapter(&Lang::Rescript, &options, Framework::Halogen).is_err());
+    }
+}
@@ -1,38 +1,272 @@
 use anyhow::Result;
 use askama::Template;
-use std::{borrow::Cow, collections::HashSet};
+use std::borrow::Cow;
 
-use super::{generate_module_name, LangTemplate};
+use super::typescript::variant_api;
+use super::{
+    breakpoints, generate_module_name, render_file, split_module_options, validate_module_name,
+    LangTemplate, TemplateOptions,
+};
+use crate::split::INTERNAL_CHUNK_NAME;
+use crate::{Aliases, Breakpoint, Chunk, Classes, CustomProperties, GeneratedFile, VariantApi};
 
 #[derive(Template)]
 #[template(path = "elm.txt")]
 pub struct ElmTemplate<'a> {
-    classes: &'a HashSet<String>,
+    class_prefix: &'a str,
+    classes: &'a Classes,
     module_name: Cow<'a, str>,
+    variants: VariantApi,
+    breakpoints: Vec<Breakpoint>,
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
+    /// The classes, aliases, variant functions, breakpoint, animation, and guard helpers exposed by the module
+    exposed: Vec<String>,
 }
 
 impl<'a> LangTemplate<'a> for ElmTemplate<'a> {
-    fn new(
-        output_directory: &'a str,
-        output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self> {
-        let module_name = generate_module_name(output_directory, output_filename)?;
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self> {
+        let module_name = generate_module_name(options)?;
+
+        validate_module_name(&module_name)?;
+
+        let variants = variant_api(options, classes);
+
+        let mut exposed = Vec::with_capacity(classes.len());
+
+        for class in classes.keys() {
+            if !variants.is_covered(class) {
+                exposed.push(filters::name(class, &options.class_prefix)?);
+            }
+        }
+
+        for alias in options.aliases.keys() {
+            exposed.push(filters::name(alias, &options.class_prefix)?);
+        }
+
+        for function in variants.functions() {
+            exposed.push(filters::classes_alias(&function.variant)?);
+            exposed.push(filters::name(&function.variant, &options.class_prefix)?);
+        }
+
+        let breakpoints = breakpoints(options, classes);
+
+        if !breakpoints.is_empty() {
+            exposed.extend(
+                [
+                    "Breakpoint(..)",
+                    "breakpoints",
+                    "breakpointQuery",
+                    "breakpointMinWidth",
+                    "breakpointMaxWidth",
+                ]
+                .iter()
+                .map(|name| name.to_string()),
+            );
+        }
+
+        if !options.animations.is_empty() {
+            exposed.extend(
+                ["AnimationName(..)", "animationNames", "animationName"]
+                    .iter()
+                    .map(|name| name.to_string()),
+            );
+        }
+
+        if options.guards {
+            exposed.extend(
+                ["fromString", "isCssClass", "parseClasses", "decoder"]
+                    .iter()
+                    .map(|name| name.to_string()),
+            );
+        }
 
         Ok(ElmTemplate {
+            class_prefix: &options.class_prefix,
             classes,
             module_name,
+            variants,
+            breakpoints,
+            animations: &options.animations,
+            guards: options.guards,
+            aliases: &options.aliases,
+            exposed,
         })
     }
 }
 
+#[derive(Template)]
+#[template(path = "elm_internal.txt")]
+struct ElmInternalTemplate {
+    module_name: String,
+}
+
+#[derive(Template)]
+#[template(path = "elm_chunk.txt")]
+struct ElmChunkTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    module_name: String,
+    internal_module_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "elm_aggregator.txt")]
+struct ElmAggregatorTemplate<'a> {
+    module_name: String,
+    internal_module_name: &'a str,
+    chunk_module_names: Vec<String>,
+}
+
+/// See [`super::generate_split`], Elm can't re-export values so the aggregator
+/// module only exposes the type and its helpers
+pub fn generate_split(options: &TemplateOptions, chunks: &[Chunk]) -> Result<Vec<GeneratedFile>> {
+    let internal_options = split_module_options(options, INTERNAL_CHUNK_NAME);
+
+    let internal_module_name = module_name(&internal_options)?;
+
+    let mut files = vec![render_file(
+        &internal_options,
+        &ElmInternalTemplate {
+            module_name: internal_module_name.clone(),
+        },
+        "elm",
+    )?];
+
+    let mut chunk_module_names = Vec::with_capacity(chunks.len());
+
+    for chunk in chunks {
+        let chunk_options = split_module_options(options, &chunk.name);
+
+        let module_name = module_name(&chunk_options)?;
+
+        chunk_module_names.push(module_name.clone());
+
+        files.push(render_file(
+            &chunk_options,
+            &ElmChunkTemplate {
+                class_prefix: &options.class_prefix,
+                classes: &chunk.classes,
+                module_name,
+                internal_module_name: &internal_module_name,
+            },
+            "elm",
+        )?);
+    }
+
+    files.push(render_file(
+        options,
+        &ElmAggregatorTemplate {
+            module_name: module_name(options)?,
+            internal_module_name: &internal_module_name,
+            chunk_module_names,
+        },
+        "elm",
+    )?);
+
+    Ok(files)
+}
+
+#[derive(Template)]
+#[template(path = "elm_tokens.txt")]
+struct ElmTokensTemplate<'a> {
+    module_name: String,
+    custom_properties: &'a CustomProperties,
+}
+
+/// See [`super::generate_tokens`]
+pub fn generate_tokens(
+    options: &TemplateOptions,
+    custom_properties: &CustomProperties,
+) -> Result<Vec<GeneratedFile>> {
+    Ok(vec![render_file(
+        options,
+        &ElmTokensTemplate {
+            module_name: module_name(options)?,
+            custom_properties,
+        },
+        "elm",
+    )?])
+}
+
+fn module_name(options: &TemplateOptions) -> Result<String> {
+    let module_name = generate_module_name(options)?;
+
+    validate_module_name(&module_name)?;
+
+    Ok(module_name.into_owned())
+}
+
 mod filters {
     use askama::Result;
     use convert_case::{Case, Casing};
 
-    use crate::utils::escape_class_name;
+    use crate::utils::{
+        alias_doc_lines, class_doc_lines, constructor_identifier, custom_property_constructor,
+        escape_class_name, escape_keyword, float_literal, string_literal, strip_class_prefix,
+    };
+    use crate::{ClassInfo, CustomProperty};
+
+    /// The reserved words of Elm, which the classes and variants can't be named after
+    const KEYWORDS: &[&str] = &[
+        "alias", "as", "case", "effect", "else", "exposing", "if", "import", "in", "infix", "let",
+        "module", "of", "port", "then", "type", "where",
+    ];
+
+    /// The identifier of a class, without the Tailwind prefix (`tw-`) of the stylesheet
+    pub fn name(class: &str, prefix: &str) -> Result<String> {
+        Ok(escape_keyword(
+            escape_class_name(&strip_class_prefix(class, prefix)).to_case(Case::Camel),
+            KEYWORDS,
+        ))
+    }
+
+    pub fn token_constructor(custom_property: &CustomProperty) -> Result<String> {
+        Ok(custom_property_constructor(&custom_property.name))
+    }
+
+    pub fn constructor(name: &str) -> Result<String> {
+        Ok(constructor_identifier(name))
+    }
+
+    pub fn float(value: &f64) -> Result<String> {
+        Ok(float_literal(*value))
+    }
+
+    pub fn string(value: &str) -> Result<String> {
+        Ok(string_literal(value))
+    }
+
+    /// The record of the classes a variant function applies to (`mdClasses`)
+    pub fn classes_record(variant: &str) -> Result<String> {
+        Ok(format!(
+            "{}Classes",
+            escape_class_name(variant).to_case(Case::Camel)
+        ))
+    }
+
+    /// The type of the record of the classes a variant function applies to (`MdClasses`)
+    pub fn classes_alias(variant: &str) -> Result<String> {
+        Ok(format!(
+            "{}Classes",
+            escape_class_name(variant).to_case(Case::Pascal)
+        ))
+    }
+
+    pub fn doc(info: &ClassInfo) -> Result<String> {
+        Ok(doc_comment(&class_doc_lines(info)))
+    }
+
+    pub fn alias_doc(members: &[String]) -> Result<String> {
+        Ok(doc_comment(&alias_doc_lines(members)))
+    }
+
+    fn doc_comment(lines: &[String]) -> String {
+        if lines.is_empty() {
+            return String::new();
+        }
 
-    pub fn name(class: &str) -> Result<String> {
-        Ok(escape_class_name(class).to_case(Case::Camel))
+        format!("{{-| {}\n-}}\n", lines.join("\n").replace("-}", "- }"))
     }
 }
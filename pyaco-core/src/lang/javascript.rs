@@ -0,0 +1,452 @@
+use anyhow::{anyhow, Result};
+use askama::Template;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::typescript::{namespaces, variant_api};
+use super::{breakpoints, render_file, Lang, TemplateOptions};
+use crate::{Aliases, Breakpoint, Category, Classes, GeneratedFile, VariantApi};
+
+/// The module formats of the JavaScript emitted by the TypeScript generators
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsFormat {
+    /// ES modules (`.mjs`)
+    Esm,
+    /// CommonJS modules (`.cjs`)
+    Cjs,
+    /// Both ES and CommonJS modules
+    Both,
+}
+
+impl FromStr for JsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "esm" => Ok(JsFormat::Esm),
+            "cjs" => Ok(JsFormat::Cjs),
+            "both" => Ok(JsFormat::Both),
+            unknown => Err(format!(
+                "\"{}\" is not a valid JavaScript format, should be one of (esm|cjs|both)",
+                unknown
+            )),
+        }
+    }
+}
+
+impl JsFormat {
+    /// The extensions of the emitted runtime files, each paired with the extension of its
+    /// declaration file, so TypeScript resolves the declarations of the matching module format
+    fn extensions(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            JsFormat::Esm => &[("mjs", "d.mts")],
+            JsFormat::Cjs => &[("cjs", "d.cts")],
+            JsFormat::Both => &[("mjs", "d.mts"), ("cjs", "d.cts")],
+        }
+    }
+}
+
+/// How a runtime file declares its exports: ES modules export each declaration,
+/// CommonJS modules assign them to `exports` at the end of the file
+struct ModuleExports {
+    cjs: bool,
+    declare: &'static str,
+    exports: Vec<String>,
+}
+
+impl ModuleExports {
+    fn new(extension: &str, names: &[String]) -> Self {
+        let cjs = extension == "cjs";
+
+        ModuleExports {
+            cjs,
+            declare: if cjs { "const" } else { "export const" },
+            exports: if cjs { names.to_vec() } else { Vec::new() },
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "typescript_js.txt")]
+struct TypescriptJsTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    namespaces: &'a BTreeMap<Category, Vec<&'a String>>,
+    variants: &'a VariantApi,
+    breakpoints: &'a [Breakpoint],
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
+    cjs: bool,
+    declare: &'static str,
+    exports: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "typescript_dts.txt")]
+struct TypescriptDtsTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    namespaces: &'a BTreeMap<Category, Vec<&'a String>>,
+    variants: &'a VariantApi,
+    breakpoints: &'a [Breakpoint],
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
+}
+
+#[derive(Template)]
+#[template(path = "typescript_type_1_js.txt")]
+struct TypescriptType1JsTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    variants: &'a VariantApi,
+    breakpoints: &'a [Breakpoint],
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
+    cjs: bool,
+    declare: &'static str,
+    exports: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "typescript_type_1_dts.txt")]
+struct TypescriptType1DtsTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    variants: &'a VariantApi,
+    breakpoints: &'a [Breakpoint],
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
+}
+
+#[derive(Template)]
+#[template(path = "typescript_type_2_js.txt")]
+struct TypescriptType2JsTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    breakpoints: &'a [Breakpoint],
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
+    cjs: bool,
+    declare: &'static str,
+    exports: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "typescript_type_2_dts.txt")]
+struct TypescriptType2DtsTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    breakpoints: &'a [Breakpoint],
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
+}
+
+/// Emits a TypeScript target as JavaScript modules (`.mjs`, `.cjs`), each with its declaration
+/// file (`.d.mts`, `.d.cts`), keeping the typing strategy of the generator
+pub fn generate_javascript(
+    lang: &Lang,
+    options: &TemplateOptions,
+    classes: &Classes,
+    format: JsFormat,
+) -> Result<Vec<GeneratedFile>> {
+    let breakpoints = breakpoints(options, classes);
+
+    let animations = options.animations.as_slice();
+
+    let guards = options.guards;
+
+    let aliases = &options.aliases;
+
+    let mut files = Vec::with_capacity(format.extensions().len() * 2);
+
+    match lang {
+        Lang::Typescript => {
+            let variants = variant_api(options, classes);
+
+            let namespaces = namespaces(options, classes, &variants);
+
+            let mut names = vec!["join".to_string()];
+
+            for class in classes.keys() {
+                if !variants.is_covered(class) {
+                    names.push(filters::name(class, &options.class_prefix)?);
+                }
+            }
+
+            names.extend(alias_names(aliases, &options.class_prefix)?);
+
+            for function in variants.functions() {
+                names.push(filters::name(&function.variant, &options.class_prefix)?);
+            }
+
+            names.extend(namespaces.keys().map(ToString::to_string));
+
+            names.extend(extra_names(&breakpoints, animations));
+
+            if guards {
+                names.extend(
+                    [
+                        "isCssClass",
+                        "isCssClassName",
+                        "parseCssClass",
+                        "parseClasses",
+                    ]
+                    .iter()
+                    .map(|name| name.to_string()),
+                );
+            }
+
+            let declarations = TypescriptDtsTemplate {
+                class_prefix: &options.class_prefix,
+                classes,
+                namespaces: &namespaces,
+                variants: &variants,
+                breakpoints: &breakpoints,
+                animations,
+                guards,
+                aliases,
+            };
+
+            for (extension, declaration_extension) in format.extensions() {
+                let ModuleExports {
+                    cjs,
+                    declare,
+                    exports,
+                } = ModuleExports::new(extension, &names);
+
+                files.push(render_file(
+                    options,
+                    &TypescriptJsTemplate {
+                        class_prefix: &options.class_prefix,
+                        classes,
+                        namespaces: &namespaces,
+                        variants: &variants,
+                        breakpoints: &breakpoints,
+                        animations,
+                        guards,
+                        aliases,
+                        cjs,
+                        declare,
+                        exports,
+                    },
+                    extension,
+                )?);
+
+                files.push(render_file(options, &declarations, declaration_extension)?);
+            }
+        }
+        Lang::TypescriptType1 => {
+            let variants = variant_api(options, classes);
+
+            let mut names = vec!["join".to_string()];
+
+            names.extend(alias_names(aliases, &options.class_prefix)?);
+
+            for function in variants.functions() {
+                names.push(filters::name(&function.variant, &options.class_prefix)?);
+            }
+
+            names.extend(extra_names(&breakpoints, animations));
+
+            names.extend(guard_names(guards));
+
+            let declarations = TypescriptType1DtsTemplate {
+                class_prefix: &options.class_prefix,
+                classes,
+                variants: &variants,
+                breakpoints: &breakpoints,
+                animations,
+                guards,
+                aliases,
+            };
+
+            for (extension, declaration_extension) in format.extensions() {
+                let ModuleExports {
+                    cjs,
+                    declare,
+                    exports,
+                } = ModuleExports::new(extension, &names);
+
+                files.push(render_file(
+                    options,
+                    &TypescriptType1JsTemplate {
+                        class_prefix: &options.class_prefix,
+                        classes,
+                        variants: &variants,
+                        breakpoints: &breakpoints,
+                        animations,
+                        guards,
+                        aliases,
+                        cjs,
+                        declare,
+                        exports,
+                    },
+                    extension,
+                )?);
+
+                files.push(render_file(options, &declarations, declaration_extension)?);
+            }
+        }
+        Lang::TypescriptType2 => {
+            let mut names = vec!["css".to_string()];
+
+            names.extend(alias_names(aliases, &options.class_prefix)?);
+
+            names.extend(extra_names(&breakpoints, animations));
+
+            names.extend(guard_names(guards));
+
+            let declarations = TypescriptType2DtsTemplate {
+                class_prefix: &options.class_prefix,
+                classes,
+                breakpoints: &breakpoints,
+                animations,
+                guards,
+                aliases,
+            };
+
+            for (extension, declaration_extension) in format.extensions() {
+                let ModuleExports {
+                    cjs,
+                    declare,
+                    exports,
+                } = ModuleExports::new(extension, &names);
+
+                files.push(render_file(
+                    options,
+                    &TypescriptType2JsTemplate {
+                        class_prefix: &options.class_prefix,
+                        classes,
+                        breakpoints: &breakpoints,
+                        animations,
+                        guards,
+                        aliases,
+                        cjs,
+                        declare,
+                        exports,
+                    },
+                    extension,
+                )?);
+
+                files.push(render_file(options, &declarations, declaration_extension)?);
+            }
+        }
+        _ => {
+            return Err(anyhow!(
+                "JavaScript can only be emitted by the typescript generators"
+            ))
+        }
+    }
+
+    Ok(files)
+}
+
+/// The names exported for the aliases
+fn alias_names(aliases: &Aliases, class_prefix: &str) -> Result<Vec<String>> {
+    Ok(aliases
+        .keys()
+        .map(|alias| filters::name(alias, class_prefix))
+        .collect::<askama::Result<_>>()?)
+}
+
+/// The names exported for the breakpoints and animations, when generated
+fn extra_names(breakpoints: &[Breakpoint], animations: &[String]) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if !breakpoints.is_empty() {
+        names.push("breakpoints".to_string());
+    }
+
+    if !animations.is_empty() {
+        names.push("animationNames".to_string());
+    }
+
+    names
+}
+
+/// The names exported for the guards of the string typed generators, when generated
+fn guard_names(guards: bool) -> Vec<String> {
+    if guards {
+        vec!["isCssClass".to_string(), "parseClasses".to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+mod filters {
+    pub use super::super::typescript::filters::{alias_doc, doc, name, namespace_member_doc};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClassInfo;
+
+    #[test]
+    fn it_emits_modules_with_declarations() {
+        let mut classes = Classes::new();
+
+        classes.insert("p-2".to_string(), ClassInfo::default());
+
+        let options = TemplateOptions {
+            guards: true,
+            ..TemplateOptions::new("generated", "css")
+        };
+
+        let files = generate_javascript(&Lang::TypescriptType1, &options, &classes, JsFormat::Both)
+            .unwrap();
+
+        let paths = files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            [
+                "generated/css.mjs",
+                "generated/css.d.mts",
+                "generated/css.cjs",
+                "generated/css.d.cts"
+            ]
+        );
+
+        assert!(files[0].code.contains("export const isCssClass ="));
+        assert!(!files[0].code.contains("exports."));
+
+        assert!(files[2].code.contains("\nconst isCssClass ="));
+        assert!(files[2]
+            .code
+            .contains("exports.join = join;\nexports.isCssClass = isCssClass;"));
+
+        assert!(files[1].code.contains(
+            "export declare const isCssClass: (className: string) => className is CssClass;"
+        ));
+        assert_eq!(files[1].code, files[3].code);
+
+        let files =
+            generate_javascript(&Lang::Typescript, &options, &classes, JsFormat::Cjs).unwrap();
+
+        let paths = files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, ["generated/css.cjs", "generated/css.d.cts"]);
+
+        assert!(files[0]
+            .code
+            .contains("exports.isCssClassName = isCssClassName;"));
+        assert!(files[1].code.contains(
+            "export declare const isCssClassName: (value: unknown) => value is CssClassName;"
+        ));
+
+        assert!(generate_javascript(&Lang::Elm, &options, &classes, JsFormat::Esm).is_err());
+    }
+}
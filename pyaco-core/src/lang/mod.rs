@@ -1,33 +1,42 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use askama::Template;
-use log::info;
 use std::borrow::Cow;
-use std::collections::HashSet;
-use std::fmt::Display;
-use std::fs::File;
-use std::io::Write;
+use std::env::current_dir;
 use std::path::Component;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use crate::{
+    extract_breakpoints, resolve_path, Aliases, Breakpoint, Chunk, Classes, CustomProperties,
+    GeneratedFile,
+};
+use source_roots::detect_source_roots;
+
+pub use super::adapters::{generate_adapter, Framework};
 pub use super::elm::ElmTemplate;
+pub use super::javascript::{generate_javascript, JsFormat};
 pub use super::purescript::PurescriptTemplate;
 pub use super::rescript::RescriptTemplate;
 pub use super::rescript::RescriptiTemplate;
 pub use super::rescript_type::RescriptTypeTemplate;
-pub use super::typescript::TypescriptTemplate;
+pub use super::runtime::RuntimeTemplate;
+pub use super::typescript::{TypescriptStyle, TypescriptTemplate};
 pub use super::typescript_type_1::TypescriptType1Template;
 pub use super::typescript_type_2::TypescriptType2Template;
 
+pub mod adapters;
 pub mod elm;
+pub mod javascript;
 pub mod purescript;
 pub mod rescript;
 pub mod rescript_type;
+pub mod runtime;
+mod source_roots;
 pub mod typescript;
 pub mod typescript_type_1;
 pub mod typescript_type_2;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Lang {
     Elm,
     Purescript,
@@ -58,56 +67,514 @@ impl FromStr for Lang {
     }
 }
 
+/// Where and how the code of a target is generated
+#[derive(Clone, Debug, Default)]
+pub struct TemplateOptions {
+    pub output_directory: String,
+    pub output_filename: String,
+    /// Directory module names are relative to (Elm and PureScript), detected from the
+    /// elm.json or spago config of the current directory when not provided
+    pub source_root: Option<PathBuf>,
+    /// Also group the identifiers into namespaces by utility category (TypeScript and ReScript)
+    pub namespaces: bool,
+    /// Generate functions for the variants (`md(p2)`) instead of a constant per class with
+    /// variants (`mdP2`), see [`crate::VariantApi`] (TypeScript, ReScript, and Elm)
+    pub variants: bool,
+    /// Also generate a `Breakpoint` enum from the media queries, see [`crate::extract_breakpoints`]
+    pub breakpoints: bool,
+    /// The `@keyframes` names generated as a typed `AnimationName`, none by default
+    /// (see [`crate::Animation`])
+    pub animations: Vec<String>,
+    /// Also generate runtime guards and parsers checking strings against the classes
+    /// (`isCssClass`, `parseClasses`, and a json decoder in Elm)
+    pub guards: bool,
+    /// How the `typescript` generator declares the classes
+    pub typescript_style: TypescriptStyle,
+    /// The aliases generated as composite constants, validated against the classes
+    /// (see [`crate::validate_aliases`])
+    pub aliases: Aliases,
+    /// The Tailwind `prefix` of the classes (`tw-`), left out of the identifiers (`twP2` is `p2`)
+    /// but kept in the class names
+    pub class_prefix: String,
+}
+
+impl TemplateOptions {
+    pub fn new<D, F>(output_directory: D, output_filename: F) -> Self
+    where
+        D: Into<String>,
+        F: Into<String>,
+    {
+        TemplateOptions {
+            output_directory: output_directory.into(),
+            output_filename: output_filename.into(),
+            ..Default::default()
+        }
+    }
+}
+
 pub trait LangTemplate<'a>: Template + Sized {
-    fn new(
-        output_directory: &'a str,
-        output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self>;
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self>;
 
-    fn write_to_file<P>(&self, path: P) -> Result<()>
+    /// Renders the code in memory, without writing it
+    fn generate<P>(&self, path: P) -> Result<GeneratedFile>
     where
-        P: AsRef<Path> + Display,
+        P: Into<String>,
     {
-        info!("Writing code into {}", path);
+        Ok(GeneratedFile::new(path, self.render()?))
+    }
+}
 
-        let code = self.render()?;
+/// Generates the code of a target split into several modules (see [`crate::split_classes`]):
+/// an internal module shared by the chunks, one module per chunk, and an aggregator module
+/// exposing them all. The aggregator takes the output path, the other modules are generated
+/// in a directory named after the output filename.
+pub fn generate_split(
+    lang: &Lang,
+    options: &TemplateOptions,
+    chunks: &[Chunk],
+) -> Result<Vec<GeneratedFile>> {
+    match lang {
+        Lang::Elm => elm::generate_split(options, chunks),
+        Lang::Purescript => purescript::generate_split(options, chunks),
+        Lang::Rescript => rescript::generate_split(options, chunks),
+        Lang::RescriptType => rescript_type::generate_split(options, chunks),
+        Lang::Typescript => typescript::generate_split(options, chunks),
+        Lang::TypescriptType1 => typescript_type_1::generate_split(options, chunks),
+        Lang::TypescriptType2 => typescript_type_2::generate_split(options, chunks),
+    }
+}
 
-        let mut output = File::create(path)?;
+/// The breakpoints generated for a target, none unless enabled
+pub(crate) fn breakpoints(options: &TemplateOptions, classes: &Classes) -> Vec<Breakpoint> {
+    if options.breakpoints {
+        extract_breakpoints(classes)
+    } else {
+        Vec::new()
+    }
+}
 
-        output.write_all(code.as_bytes())?;
+/// Generates a module typing the custom properties of the stylesheet (see [`crate::CustomProperty`])
+/// next to the target, named after the output filename suffixed with `Tokens`
+pub fn generate_tokens(
+    lang: &Lang,
+    options: &TemplateOptions,
+    custom_properties: &CustomProperties,
+) -> Result<Vec<GeneratedFile>> {
+    let options = TemplateOptions {
+        output_filename: format!("{}Tokens", options.output_filename),
+        ..options.clone()
+    };
 
-        Ok(())
+    match lang {
+        Lang::Elm => elm::generate_tokens(&options, custom_properties),
+        Lang::Purescript => purescript::generate_tokens(&options, custom_properties),
+        Lang::Rescript | Lang::RescriptType => {
+            rescript::generate_tokens(&options, custom_properties)
+        }
+        Lang::Typescript | Lang::TypescriptType1 | Lang::TypescriptType2 => {
+            typescript::generate_tokens(&options, custom_properties)
+        }
     }
 }
 
-/// Used by Elm and PureScript to generate their module name based on the directory and the filename
-pub(crate) fn generate_module_name<'a>(
-    output_directory: &'a str,
-    output_filename: &'a str,
-) -> Result<Cow<'a, str>> {
-    let path = Path::new(output_directory);
+/// The options of a module generated in the directory of a split target
+pub(crate) fn split_module_options(options: &TemplateOptions, filename: &str) -> TemplateOptions {
+    TemplateOptions {
+        output_directory: Path::new(&options.output_directory)
+            .join(&options.output_filename)
+            .to_string_lossy()
+            .into_owned(),
+        output_filename: filename.to_string(),
+        ..options.clone()
+    }
+}
+
+/// Renders a template into the file described by the options
+pub(crate) fn render_file<T>(
+    options: &TemplateOptions,
+    template: &T,
+    extension: &str,
+) -> Result<GeneratedFile>
+where
+    T: Template,
+{
+    let path = resolve_path(
+        &options.output_directory,
+        &options.output_filename,
+        extension,
+    )?;
+
+    Ok(GeneratedFile::new(path, template.render()?))
+}
+
+/// Used by Elm and PureScript to generate their module name based on the directory and the filename,
+/// the directory is taken relatively to the source root (see [`TemplateOptions::source_root`])
+pub(crate) fn generate_module_name(options: &TemplateOptions) -> Result<Cow<'_, str>> {
+    let output_directory = Path::new(&options.output_directory);
+
+    let source_roots = match options.source_root {
+        Some(ref source_root) => vec![source_root.clone()],
+        None => detect_source_roots()?,
+    };
+
+    let relative_directory = if source_roots.is_empty() {
+        output_directory.to_path_buf()
+    } else {
+        let output_directory = normalize_path(output_directory)?;
 
-    let base = path.components().into_iter().try_fold(
-        "".into(),
-        |acc, component| -> Result<Cow<'a, str>> {
-            if let Component::Normal(part) = component {
-                let part = part.to_string_lossy();
+        let mut relative_directories = Vec::with_capacity(source_roots.len());
 
-                if acc.is_empty() {
-                    return Ok(part);
-                }
+        for source_root in source_roots.iter() {
+            let source_root = normalize_path(source_root)?;
 
-                return Ok(format!("{}.{}", acc, part).into());
+            if let Ok(relative_directory) = output_directory.strip_prefix(&source_root) {
+                relative_directories.push(relative_directory);
             }
+        }
 
-            Ok(acc)
-        },
-    )?;
+        // The most nested source root wins
+        match relative_directories
+            .into_iter()
+            .min_by_key(|directory| directory.components().count())
+        {
+            Some(relative_directory) => relative_directory.to_path_buf(),
+            None => {
+                return Err(anyhow!(
+                    "output directory {} is not inside the source root ({}), the module name can't be computed",
+                    options.output_directory,
+                    source_roots
+                        .iter()
+                        .map(|source_root| source_root.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
+        }
+    };
+
+    Ok(directory_module_name(
+        &relative_directory,
+        &options.output_filename,
+    ))
+}
+
+/// The module name made of the segments of the directory followed by the filename
+/// (`Styles/Tailwind` and `Css` give `Styles.Tailwind.Css`)
+pub(crate) fn directory_module_name<'a>(directory: &Path, filename: &'a str) -> Cow<'a, str> {
+    let mut segments = directory
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if segments.is_empty() {
+        return filename.into();
+    }
+
+    segments.push(filename.to_string());
+
+    segments.join(".").into()
+}
+
+/// Elm and PureScript module names are made of capitalized identifiers separated by dots
+pub(crate) fn validate_module_name(module_name: &str) -> Result<()> {
+    for segment in module_name.split('.') {
+        let mut chars = segment.chars();
+
+        let is_valid = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        if !is_valid {
+            return Err(anyhow!(
+                "invalid module name {}: \"{}\" is not a capitalized identifier, make sure the output directory (relative to the source root) and filename only contain capitalized identifiers, or set the source root explicitly",
+                module_name,
+                segment
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lexically resolves `.` and `..`, relative paths are resolved against the current directory
+fn normalize_path(path: &Path) -> Result<PathBuf> {
+    let mut normalized = if path.is_absolute() {
+        PathBuf::new()
+    } else {
+        current_dir()?
+    };
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module_name(
+        output_directory: &str,
+        output_filename: &str,
+        source_root: Option<&str>,
+    ) -> Result<String> {
+        let options = TemplateOptions {
+            source_root: source_root.map(PathBuf::from),
+            ..TemplateOptions::new(output_directory, output_filename)
+        };
+
+        Ok(generate_module_name(&options)?.into_owned())
+    }
+
+    #[test]
+    fn it_generates_module_names_relative_to_the_source_root() {
+        assert_eq!(
+            module_name("src/Styles", "Tailwind", Some("src")).unwrap(),
+            "Styles.Tailwind"
+        );
 
-    if base.is_empty() {
-        return Ok(output_filename.into());
+        assert_eq!(
+            module_name("./src/Styles/", "Tailwind", Some("./src")).unwrap(),
+            "Styles.Tailwind"
+        );
+
+        assert_eq!(
+            module_name("src", "Tailwind", Some("src")).unwrap(),
+            "Tailwind"
+        );
+
+        assert!(module_name("lib/Styles", "Tailwind", Some("src")).is_err());
+    }
+
+    #[test]
+    fn it_uses_the_whole_directory_without_source_root() {
+        assert_eq!(
+            module_name("./Foo/Bar", "Baz", None).unwrap(),
+            "Foo.Bar.Baz"
+        );
+        assert_eq!(module_name("./", "Css", None).unwrap(), "Css");
+    }
+
+    fn render_guards<'a, T>(options: &'a TemplateOptions, classes: &'a Classes) -> String
+    where
+        T: LangTemplate<'a>,
+    {
+        T::new(options, classes).unwrap().render().unwrap()
     }
 
-    Ok(format!("{}.{}", base, output_filename).into())
+    #[test]
+    fn it_generates_the_guards_of_every_language() {
+        let classes = crate::extract_stylesheet_from_text(".p-2 {} .w-1\\/2 {}")
+            .unwrap()
+            .classes;
+
+        let options = TemplateOptions {
+            guards: true,
+            source_root: Some(PathBuf::from("src")),
+            ..TemplateOptions::new("src/Styles", "Css")
+        };
+
+        let elm = render_guards::<ElmTemplate>(&options, &classes);
+
+        assert!(elm.contains("        , ( \"w-1/2\", w1Over2 )"));
+        assert!(elm.contains("isCssClass className =\n    Dict.member className cssClasses"));
+        assert!(elm.contains(
+            "parseClasses : String -> { valid : List CssClass, rejected : List String }"
+        ));
+        assert!(elm.contains("decoder : Decode.Decoder CssClass"));
+        assert!(elm.contains("Decode.fail (\"Unknown css class \" ++ className)"));
+
+        let purescript = render_guards::<PurescriptTemplate>(&options, &classes);
+
+        assert!(purescript.contains("    , Tuple \"w-1/2\" w1Over2"));
+        assert!(purescript.contains("isCssClass className = Object.member className cssClasses"));
+        assert!(purescript.contains(
+            "parseClasses :: String -> { valid :: Array CssClass, rejected :: Array String }"
+        ));
+
+        for rescript in [
+            render_guards::<RescriptTemplate>(&options, &classes),
+            render_guards::<RescriptTypeTemplate>(&options, &classes),
+        ] {
+            assert!(rescript.contains("let cssClasses: Js.Dict.t<cssClass> = Js.Dict.fromArray(["));
+            assert!(rescript.contains(
+                "let isCssClass = className => fromString(className)->Belt.Option.isSome"
+            ));
+            assert!(rescript.contains("let parseClasses = classNames => {"));
+        }
+
+        let rescripti = render_guards::<RescriptiTemplate>(&options, &classes);
+
+        assert!(rescripti.contains("let isCssClass: string => bool"));
+        assert!(rescripti.contains("let parseClasses: string => parsedClasses"));
+
+        let typescript = render_guards::<TypescriptTemplate>(&options, &classes);
+
+        assert!(typescript.contains("  [\"w-1/2\", buildCssClass(\"w-1/2\")],"));
+        assert!(
+            typescript.contains("export const isCssClass = (value: unknown): value is CssClass =>")
+        );
+        assert!(typescript.contains("export type CssClassName =\n  | \"p-2\"\n  | \"w-1/2\";"));
+        assert!(typescript.contains(
+            "export const isCssClassName = (value: unknown): value is CssClassName =>\n  typeof value === \"string\" && cssClasses.has(value);"
+        ));
+        assert!(typescript
+            .contains("export const parseCssClass = (className: string): CssClass | undefined =>"));
+        assert!(typescript.contains("): { valid: CssClass[]; rejected: string[] } => {"));
+
+        for typescript in [
+            render_guards::<TypescriptType1Template>(&options, &classes),
+            render_guards::<TypescriptType2Template>(&options, &classes),
+        ] {
+            assert!(typescript.contains(
+                "const cssClasses: ReadonlySet<string> = new Set<CssClass>([\n  \"p-2\",\n  \"w-1/2\",\n]);"
+            ));
+            assert!(typescript.contains(
+                "export const isCssClass = (className: string): className is CssClass =>"
+            ));
+            assert!(typescript.contains("): { valid: CssClass[]; rejected: string[] } => {"));
+        }
+
+        let options = TemplateOptions {
+            guards: false,
+            ..options
+        };
+
+        assert!(!render_guards::<ElmTemplate>(&options, &classes).contains("isCssClass"));
+        assert!(!render_guards::<TypescriptTemplate>(&options, &classes).contains("isCssClass"));
+    }
+
+    #[test]
+    fn it_escapes_the_keywords_of_every_language() {
+        let classes = crate::extract_stylesheet_from_text(
+            ".p-2 {} .default\\:p-2 {} .open\\:p-2 {} .in\\:p-2 {}",
+        )
+        .unwrap()
+        .classes;
+
+        let options = TemplateOptions {
+            variants: true,
+            source_root: Some(PathBuf::from("src")),
+            ..TemplateOptions::new("src/Styles", "Css")
+        };
+
+        let typescript = render_guards::<TypescriptTemplate>(&options, &classes);
+
+        assert!(typescript.contains("export const default_ = <"));
+        assert!(typescript.contains("export const in_ = <"));
+        assert!(typescript.contains("export const open = <"));
+
+        let rescript = render_guards::<RescriptTemplate>(&options, &classes);
+
+        assert!(rescript.contains("let default = (cssClass: ["));
+        assert!(rescript.contains("let in_ = (cssClass: ["));
+        assert!(rescript.contains("let open_ = (cssClass: ["));
+
+        let elm = render_guards::<ElmTemplate>(&options, &classes);
+
+        assert!(elm.contains("in_ : (InClasses -> CssClass) -> CssClass"));
+        assert!(elm.contains("in_ accessor =\n    accessor inClasses"));
+    }
+
+    #[test]
+    fn it_refers_to_the_deprecated_classes_by_their_names() {
+        let mut classes = crate::extract_stylesheet_from_text(".p-2 {} .p-3 {}")
+            .unwrap()
+            .classes;
+
+        crate::apply_deprecations(
+            &mut classes,
+            &crate::parse_deprecations([("p-2", "use .p-3 instead")]),
+        );
+
+        let options = TemplateOptions {
+            guards: true,
+            namespaces: true,
+            ..TemplateOptions::new("src/Styles", "Css")
+        };
+
+        let rescript = render_guards::<RescriptTemplate>(&options, &classes);
+
+        assert!(
+            rescript.contains("  @deprecated(\"use .p-3 instead\")\n  let p2 = CssClass(\"p-2\")")
+        );
+        assert!(rescript.contains("  (\"p-2\", CssClass(\"p-2\")),"));
+        assert!(!rescript.contains("= p2"));
+
+        let rescripti = render_guards::<RescriptiTemplate>(&options, &classes);
+
+        assert!(rescripti.contains("  @deprecated(\"use .p-3 instead\")\n  let p2: cssClass"));
+
+        let typescript = render_guards::<TypescriptTemplate>(&options, &classes);
+
+        assert!(typescript
+            .contains("   * @deprecated use .p-3 instead\n   */\n  p2: buildCssClass(\"p-2\"),"));
+        assert!(typescript.contains("  [\"p-2\", buildCssClass(\"p-2\")],"));
+
+        let options = TemplateOptions {
+            typescript_style: TypescriptStyle::ConstEnum,
+            ..options
+        };
+
+        let typescript = render_guards::<TypescriptTemplate>(&options, &classes);
+
+        assert!(typescript.contains("  p2: \"p-2\" as CssClass,"));
+        assert!(!typescript.contains("Css.p2"));
+    }
+
+    #[test]
+    fn it_keeps_the_class_type_opaque_in_split_mode() {
+        let classes = crate::extract_stylesheet_from_text(".p-2 {} .mt-2 {}")
+            .unwrap()
+            .classes;
+
+        let chunks = crate::split_classes(&classes, crate::SplitBy::Prefix, 100, "");
+
+        let options = TemplateOptions {
+            source_root: Some(PathBuf::from("src")),
+            ..TemplateOptions::new("src/Styles", "Css")
+        };
+
+        let file = |files: &[GeneratedFile], path: &str| {
+            files
+                .iter()
+                .find(|file| file.path.ends_with(path))
+                .unwrap()
+                .code
+                .clone()
+        };
+
+        let rescript = generate_split(&Lang::Rescript, &options, &chunks).unwrap();
+
+        assert!(file(&rescript, "Css_Internal.resi").contains("\ntype cssClass\n"));
+        assert!(file(&rescript, "Css_P.res")
+            .contains("external make: string => Css_Internal.cssClass = \"%identity\""));
+        assert!(file(&rescript, "Css_P.res").contains("let p2 = make(\"p-2\")"));
+        assert!(!file(&rescript, "Css_P.resi").contains("make"));
+
+        let purescript = generate_split(&Lang::Purescript, &options, &chunks).unwrap();
+
+        assert!(file(&purescript, "Internal.purs").contains("  CssClass,\n"));
+        assert!(!file(&purescript, "Internal.purs").contains("CssClass(..)"));
+        assert!(file(&purescript, "P.purs").contains("p2 =\n  wrap \"p-2\""));
+    }
+
+    #[test]
+    fn it_rejects_invalid_module_names() {
+        assert!(validate_module_name("Styles.Tailwind").is_ok());
+        assert!(validate_module_name("Styles.Tailwind_2").is_ok());
+        assert!(validate_module_name("src.Styles.Tailwind").is_err());
+        assert!(validate_module_name("Styles.tailwind").is_err());
+        assert!(validate_module_name("Styles.Tail-wind").is_err());
+    }
 }
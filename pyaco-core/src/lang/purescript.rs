@@ -1,40 +1,212 @@
 use anyhow::Result;
 use askama::Template;
-use std::{borrow::Cow, collections::HashSet};
+use std::borrow::Cow;
 
-use crate::LangTemplate;
+use crate::split::INTERNAL_CHUNK_NAME;
+use crate::{
+    Aliases, Breakpoint, Chunk, Classes, CustomProperties, GeneratedFile, LangTemplate,
+    TemplateOptions,
+};
 
-use super::generate_module_name;
+use super::{
+    breakpoints, generate_module_name, render_file, split_module_options, validate_module_name,
+};
 
 #[derive(Template)]
 #[template(path = "purescript.txt")]
 pub struct PurescriptTemplate<'a> {
-    classes: &'a HashSet<String>,
+    class_prefix: &'a str,
+    classes: &'a Classes,
     module_name: Cow<'a, str>,
+    breakpoints: Vec<Breakpoint>,
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
 }
 
 impl<'a> LangTemplate<'a> for PurescriptTemplate<'a> {
-    fn new(
-        output_directory: &'a str,
-        output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self> {
-        let module_name = generate_module_name(output_directory, output_filename)?;
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self> {
+        let module_name = generate_module_name(options)?;
+
+        validate_module_name(&module_name)?;
 
         Ok(PurescriptTemplate {
+            class_prefix: &options.class_prefix,
             classes,
             module_name,
+            breakpoints: breakpoints(options, classes),
+            animations: &options.animations,
+            guards: options.guards,
+            aliases: &options.aliases,
         })
     }
 }
 
+#[derive(Template)]
+#[template(path = "purescript_internal.txt")]
+struct PurescriptInternalTemplate {
+    module_name: String,
+}
+
+#[derive(Template)]
+#[template(path = "purescript_chunk.txt")]
+struct PurescriptChunkTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    module_name: String,
+    internal_module_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "purescript_aggregator.txt")]
+struct PurescriptAggregatorTemplate<'a> {
+    module_name: String,
+    internal_module_name: &'a str,
+    chunk_module_names: Vec<String>,
+}
+
+/// See [`super::generate_split`]
+pub fn generate_split(options: &TemplateOptions, chunks: &[Chunk]) -> Result<Vec<GeneratedFile>> {
+    let internal_options = split_module_options(options, INTERNAL_CHUNK_NAME);
+
+    let internal_module_name = module_name(&internal_options)?;
+
+    let mut files = vec![render_file(
+        &internal_options,
+        &PurescriptInternalTemplate {
+            module_name: internal_module_name.clone(),
+        },
+        "purs",
+    )?];
+
+    let mut chunk_module_names = Vec::with_capacity(chunks.len());
+
+    for chunk in chunks {
+        let chunk_options = split_module_options(options, &chunk.name);
+
+        let module_name = module_name(&chunk_options)?;
+
+        chunk_module_names.push(module_name.clone());
+
+        files.push(render_file(
+            &chunk_options,
+            &PurescriptChunkTemplate {
+                class_prefix: &options.class_prefix,
+                classes: &chunk.classes,
+                module_name,
+                internal_module_name: &internal_module_name,
+            },
+            "purs",
+        )?);
+    }
+
+    files.push(render_file(
+        options,
+        &PurescriptAggregatorTemplate {
+            module_name: module_name(options)?,
+            internal_module_name: &internal_module_name,
+            chunk_module_names,
+        },
+        "purs",
+    )?);
+
+    Ok(files)
+}
+
+#[derive(Template)]
+#[template(path = "purescript_tokens.txt")]
+struct PurescriptTokensTemplate<'a> {
+    module_name: String,
+    custom_properties: &'a CustomProperties,
+}
+
+/// See [`super::generate_tokens`]
+pub fn generate_tokens(
+    options: &TemplateOptions,
+    custom_properties: &CustomProperties,
+) -> Result<Vec<GeneratedFile>> {
+    Ok(vec![render_file(
+        options,
+        &PurescriptTokensTemplate {
+            module_name: module_name(options)?,
+            custom_properties,
+        },
+        "purs",
+    )?])
+}
+
+fn module_name(options: &TemplateOptions) -> Result<String> {
+    let module_name = generate_module_name(options)?;
+
+    validate_module_name(&module_name)?;
+
+    Ok(module_name.into_owned())
+}
+
 mod filters {
     use askama::Result;
     use convert_case::{Case, Casing};
 
-    use crate::utils::escape_class_name;
+    use crate::utils::{
+        alias_doc_lines, class_doc_lines, constructor_identifier, custom_property_constructor,
+        custom_property_doc_lines, escape_class_name, escape_keyword, float_literal,
+        string_literal, strip_class_prefix,
+    };
+    use crate::{ClassInfo, CustomProperty};
+
+    /// The reserved words of PureScript, which the classes and variants can't be named after
+    const KEYWORDS: &[&str] = &[
+        "ado", "case", "class", "data", "derive", "do", "else", "false", "forall", "foreign", "if",
+        "import", "in", "infix", "infixl", "infixr", "instance", "let", "module", "newtype", "of",
+        "then", "true", "type", "where",
+    ];
+
+    /// The identifier of a class, without the Tailwind prefix (`tw-`) of the stylesheet
+    pub fn name(class: &str, prefix: &str) -> Result<String> {
+        Ok(escape_keyword(
+            escape_class_name(&strip_class_prefix(class, prefix)).to_case(Case::Camel),
+            KEYWORDS,
+        ))
+    }
+
+    pub fn token_constructor(custom_property: &CustomProperty) -> Result<String> {
+        Ok(custom_property_constructor(&custom_property.name))
+    }
+
+    pub fn constructor(name: &str) -> Result<String> {
+        Ok(constructor_identifier(name))
+    }
+
+    pub fn float(value: &f64) -> Result<String> {
+        Ok(float_literal(*value))
+    }
+
+    pub fn doc(info: &ClassInfo) -> Result<String> {
+        Ok(doc_comment(&class_doc_lines(info)))
+    }
+
+    pub fn alias_doc(members: &[String]) -> Result<String> {
+        Ok(doc_comment(&alias_doc_lines(members)))
+    }
+
+    pub fn token_doc(custom_property: &CustomProperty) -> Result<String> {
+        Ok(doc_comment(&custom_property_doc_lines(custom_property)))
+    }
+
+    pub fn string(value: &str) -> Result<String> {
+        Ok(string_literal(value))
+    }
 
-    pub fn name(class: &str) -> Result<String> {
-        Ok(escape_class_name(class).to_case(Case::Camel))
+    fn doc_comment(lines: &[String]) -> String {
+        lines
+            .iter()
+            .map(|line| {
+                if line.is_empty() {
+                    "-- |\n".to_string()
+                } else {
+                    format!("-- | {}\n", line)
+                }
+            })
+            .collect()
     }
 }
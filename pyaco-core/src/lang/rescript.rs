@@ -1,48 +1,292 @@
 use anyhow::Result;
 use askama::Template;
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 
-use super::LangTemplate;
+use super::typescript::{namespaces, variant_api};
+use super::{breakpoints, render_file, split_module_options, LangTemplate, TemplateOptions};
+use crate::split::INTERNAL_CHUNK_NAME;
+use crate::{
+    Aliases, Breakpoint, Category, Chunk, Classes, CustomProperties, GeneratedFile, VariantApi,
+};
 
 #[derive(Template)]
 #[template(path = "rescript.txt")]
 pub struct RescriptTemplate<'a> {
-    classes: &'a HashSet<String>,
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    namespaces: BTreeMap<Category, Vec<&'a String>>,
+    variants: VariantApi,
+    breakpoints: Vec<Breakpoint>,
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
 }
 
 impl<'a> LangTemplate<'a> for RescriptTemplate<'a> {
-    fn new(
-        _output_directory: &'a str,
-        _output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self> {
-        Ok(Self { classes })
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self> {
+        let variants = variant_api(options, classes);
+
+        Ok(Self {
+            class_prefix: &options.class_prefix,
+            classes,
+            namespaces: namespaces(options, classes, &variants),
+            variants,
+            breakpoints: breakpoints(options, classes),
+            animations: &options.animations,
+            guards: options.guards,
+            aliases: &options.aliases,
+        })
     }
 }
 
 #[derive(Template)]
 #[template(path = "rescripti.txt")]
 pub struct RescriptiTemplate<'a> {
-    classes: &'a HashSet<String>,
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    namespaces: BTreeMap<Category, Vec<&'a String>>,
+    variants: VariantApi,
+    breakpoints: Vec<Breakpoint>,
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
 }
 
 impl<'a> LangTemplate<'a> for RescriptiTemplate<'a> {
-    fn new(
-        _output_directory: &'a str,
-        _output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self> {
-        Ok(Self { classes })
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self> {
+        let variants = variant_api(options, classes);
+
+        Ok(Self {
+            class_prefix: &options.class_prefix,
+            classes,
+            namespaces: namespaces(options, classes, &variants),
+            variants,
+            breakpoints: breakpoints(options, classes),
+            animations: &options.animations,
+            guards: options.guards,
+            aliases: &options.aliases,
+        })
+    }
+}
+
+#[derive(Template)]
+#[template(path = "rescript_internal.txt")]
+struct RescriptInternalTemplate;
+
+#[derive(Template)]
+#[template(path = "rescripti_internal.txt")]
+struct RescriptiInternalTemplate;
+
+#[derive(Template)]
+#[template(path = "rescript_chunk.txt")]
+struct RescriptChunkTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    internal_module_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "rescripti_chunk.txt")]
+struct RescriptiChunkTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    internal_module_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "rescript_aggregator.txt")]
+struct RescriptAggregatorTemplate<'a> {
+    internal_module_name: &'a str,
+    chunk_module_names: &'a [String],
+}
+
+#[derive(Template)]
+#[template(path = "rescripti_aggregator.txt")]
+struct RescriptiAggregatorTemplate<'a> {
+    internal_module_name: &'a str,
+    chunk_module_names: &'a [String],
+}
+
+/// See [`super::generate_split`], ReScript module names being global the modules
+/// are prefixed with the output filename (`Css_Spacing`)
+pub fn generate_split(options: &TemplateOptions, chunks: &[Chunk]) -> Result<Vec<GeneratedFile>> {
+    let internal_module_name = format!("{}_{}", options.output_filename, INTERNAL_CHUNK_NAME);
+
+    let internal_options = split_module_options(options, &internal_module_name);
+
+    // The interface keeps the class type abstract, the chunks build their classes from strings
+    let mut files = vec![
+        render_file(&internal_options, &RescriptInternalTemplate, "res")?,
+        render_file(&internal_options, &RescriptiInternalTemplate, "resi")?,
+    ];
+
+    let mut chunk_module_names = Vec::with_capacity(chunks.len());
+
+    for chunk in chunks {
+        let chunk_module_name = format!("{}_{}", options.output_filename, chunk.name);
+
+        let chunk_options = split_module_options(options, &chunk_module_name);
+
+        files.push(render_file(
+            &chunk_options,
+            &RescriptChunkTemplate {
+                class_prefix: &options.class_prefix,
+                classes: &chunk.classes,
+                internal_module_name: &internal_module_name,
+            },
+            "res",
+        )?);
+
+        files.push(render_file(
+            &chunk_options,
+            &RescriptiChunkTemplate {
+                class_prefix: &options.class_prefix,
+                classes: &chunk.classes,
+                internal_module_name: &internal_module_name,
+            },
+            "resi",
+        )?);
+
+        chunk_module_names.push(chunk_module_name);
     }
+
+    files.push(render_file(
+        options,
+        &RescriptAggregatorTemplate {
+            internal_module_name: &internal_module_name,
+            chunk_module_names: &chunk_module_names,
+        },
+        "res",
+    )?);
+
+    files.push(render_file(
+        options,
+        &RescriptiAggregatorTemplate {
+            internal_module_name: &internal_module_name,
+            chunk_module_names: &chunk_module_names,
+        },
+        "resi",
+    )?);
+
+    Ok(files)
+}
+
+#[derive(Template)]
+#[template(path = "rescript_tokens.txt")]
+struct RescriptTokensTemplate<'a> {
+    custom_properties: &'a CustomProperties,
 }
 
-mod filters {
+/// See [`super::generate_tokens`], shared by the ReScript generators
+pub fn generate_tokens(
+    options: &TemplateOptions,
+    custom_properties: &CustomProperties,
+) -> Result<Vec<GeneratedFile>> {
+    Ok(vec![render_file(
+        options,
+        &RescriptTokensTemplate { custom_properties },
+        "res",
+    )?])
+}
+
+pub(crate) mod filters {
     use askama::Result;
     use convert_case::{Case, Casing};
 
-    use crate::utils::escape_class_name;
+    use crate::utils::{
+        alias_doc_lines, block_doc_comment, class_doc_lines, constructor_identifier,
+        custom_property_constructor, custom_property_doc_lines, escape_class_name, escape_keyword,
+        float_literal, indent_doc_comment, string_literal, strip_class_prefix,
+    };
+    use crate::{ClassInfo, Classes, CustomProperty};
+
+    /// The reserved words of ReScript, which the classes and variants can't be named after
+    const KEYWORDS: &[&str] = &[
+        "and",
+        "as",
+        "assert",
+        "await",
+        "constraint",
+        "else",
+        "exception",
+        "external",
+        "false",
+        "for",
+        "if",
+        "in",
+        "include",
+        "lazy",
+        "let",
+        "module",
+        "mutable",
+        "of",
+        "open",
+        "private",
+        "rec",
+        "switch",
+        "true",
+        "try",
+        "type",
+        "when",
+        "while",
+        "with",
+    ];
+
+    /// The identifier of a class, without the Tailwind prefix (`tw-`) of the stylesheet
+    pub fn name(class: &str, prefix: &str) -> Result<String> {
+        Ok(escape_keyword(
+            escape_class_name(&strip_class_prefix(class, prefix)).to_case(Case::Camel),
+            KEYWORDS,
+        ))
+    }
+
+    pub fn token_constructor(custom_property: &CustomProperty) -> Result<String> {
+        Ok(custom_property_constructor(&custom_property.name))
+    }
+
+    pub fn constructor(name: &str) -> Result<String> {
+        Ok(constructor_identifier(name))
+    }
+
+    pub fn float(value: &f64) -> Result<String> {
+        Ok(float_literal(*value))
+    }
+
+    /// The doc comment of a class, followed by a `@deprecated` attribute making the compiler
+    /// warn about its uses when it's deprecated
+    pub fn doc(info: &ClassInfo) -> Result<String> {
+        let mut doc = block_doc_comment(&class_doc_lines(info));
+
+        if let Some(deprecation) = &info.deprecated {
+            doc.push_str(&format!(
+                "@deprecated({})\n",
+                string_literal(&deprecation.note)
+            ));
+        }
+
+        Ok(doc)
+    }
+
+    /// The doc comment of a class declared in the module of its namespace, followed by its
+    /// `@deprecated` attribute
+    pub fn namespace_member_doc(class: &str, classes: &Classes) -> Result<String> {
+        match classes.get(class) {
+            Some(info) => Ok(indent_doc_comment(&doc(info)?)),
+            None => Ok(String::new()),
+        }
+    }
+
+    pub fn alias_doc(members: &[String]) -> Result<String> {
+        Ok(block_doc_comment(&alias_doc_lines(members)))
+    }
+
+    pub fn token_doc(custom_property: &CustomProperty) -> Result<String> {
+        Ok(block_doc_comment(&custom_property_doc_lines(
+            custom_property,
+        )))
+    }
 
-    pub fn name(class: &str) -> Result<String> {
-        Ok(escape_class_name(class).to_case(Case::Camel))
+    pub fn string(value: &str) -> Result<String> {
+        Ok(string_literal(value))
     }
 }
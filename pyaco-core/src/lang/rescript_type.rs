@@ -1,21 +1,75 @@
 use anyhow::Result;
 use askama::Template;
-use std::collections::HashSet;
 
-use super::LangTemplate;
+use super::{breakpoints, render_file, split_module_options, LangTemplate, TemplateOptions};
+use crate::{Aliases, Breakpoint, Chunk, Classes, GeneratedFile};
 
 #[derive(Template)]
 #[template(path = "rescript_type.txt")]
 pub struct RescriptTypeTemplate<'a> {
-    classes: &'a HashSet<String>,
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    breakpoints: Vec<Breakpoint>,
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
 }
 
 impl<'a> LangTemplate<'a> for RescriptTypeTemplate<'a> {
-    fn new(
-        _output_directory: &'a str,
-        _output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self> {
-        Ok(Self { classes })
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self> {
+        Ok(Self {
+            class_prefix: &options.class_prefix,
+            classes,
+            breakpoints: breakpoints(options, classes),
+            animations: &options.animations,
+            guards: options.guards,
+            aliases: &options.aliases,
+        })
     }
 }
+
+#[derive(Template)]
+#[template(path = "rescript_type_chunk.txt")]
+struct RescriptTypeChunkTemplate<'a> {
+    classes: &'a Classes,
+}
+
+#[derive(Template)]
+#[template(path = "rescript_type_aggregator.txt")]
+struct RescriptTypeAggregatorTemplate {
+    chunk_module_names: Vec<String>,
+}
+
+/// See [`super::generate_split`], the aggregator type extends the chunk types
+/// (prefixed with the output filename, ReScript module names being global)
+pub fn generate_split(options: &TemplateOptions, chunks: &[Chunk]) -> Result<Vec<GeneratedFile>> {
+    let mut files = Vec::with_capacity(chunks.len() + 1);
+
+    let mut chunk_module_names = Vec::with_capacity(chunks.len());
+
+    for chunk in chunks {
+        let chunk_module_name = format!("{}_{}", options.output_filename, chunk.name);
+
+        files.push(render_file(
+            &split_module_options(options, &chunk_module_name),
+            &RescriptTypeChunkTemplate {
+                classes: &chunk.classes,
+            },
+            "res",
+        )?);
+
+        chunk_module_names.push(chunk_module_name);
+    }
+
+    files.push(render_file(
+        options,
+        &RescriptTypeAggregatorTemplate { chunk_module_names },
+        "res",
+    )?);
+
+    Ok(files)
+}
+
+mod filters {
+    pub use super::super::rescript::filters::{alias_doc, constructor, float, name};
+}
@@ -0,0 +1,214 @@
+use anyhow::{anyhow, Result};
+use log::debug;
+use minijinja::{context, Environment};
+use std::borrow::Cow;
+use std::fs::read_to_string;
+use std::path::Path;
+
+use super::{directory_module_name, generate_module_name, TemplateOptions};
+use crate::utils::{class_identifier, strip_class_prefix};
+use crate::{Aliases, Classes, GeneratedFile};
+
+/// A user provided Jinja-like template, loaded and rendered at runtime.
+///
+/// The context is the one the built-in templates get: `classes`, `aliases` (mapping each
+/// alias to its classes), `module_name`, and the `name` filter.
+pub struct RuntimeTemplate<'a> {
+    source: String,
+    extension: String,
+    classes: Vec<&'a String>,
+    aliases: &'a Aliases,
+    module_name: Cow<'a, str>,
+    class_prefix: &'a str,
+}
+
+impl<'a> RuntimeTemplate<'a> {
+    pub fn new<P>(
+        template_path: P,
+        options: &'a TemplateOptions,
+        classes: &'a Classes,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let template_path = template_path.as_ref();
+
+        let source = read_to_string(template_path).map_err(|error| {
+            anyhow!(
+                "couldn't read template {}: {}",
+                template_path.display(),
+                error
+            )
+        })?;
+
+        // The generated file takes the template extension (`Css.svelte` generates a `.svelte` file)
+        let extension = template_path
+            .extension()
+            .map(|extension| extension.to_string_lossy().into_owned())
+            .ok_or_else(|| {
+                anyhow!(
+                    "template {} has no extension, it's required to name the generated file",
+                    template_path.display()
+                )
+            })?;
+
+        let classes = classes.keys().collect();
+
+        // Only Elm and PureScript need the output directory to be inside a source root,
+        // other templates fall back to the name made of the output directory
+        let module_name = generate_module_name(options).unwrap_or_else(|error| {
+            debug!("Using the output directory as the module name: {}", error);
+
+            directory_module_name(
+                Path::new(&options.output_directory),
+                &options.output_filename,
+            )
+        });
+
+        Ok(RuntimeTemplate {
+            source,
+            extension,
+            classes,
+            aliases: &options.aliases,
+            module_name,
+            class_prefix: &options.class_prefix,
+        })
+    }
+
+    /// The extension of the generated file
+    pub fn extension(&self) -> &str {
+        self.extension.as_str()
+    }
+
+    pub fn render(&self) -> Result<String> {
+        let mut env = Environment::new();
+
+        let class_prefix = self.class_prefix.to_string();
+
+        env.add_filter("name", move |class: &str| {
+            class_identifier(&strip_class_prefix(class, &class_prefix))
+        });
+
+        let code = env.render_str(
+            self.source.as_str(),
+            context! {
+                classes => self.classes,
+                aliases => self.aliases,
+                module_name => self.module_name,
+            },
+        )?;
+
+        Ok(code)
+    }
+
+    pub fn generate<P>(&self, path: P) -> Result<GeneratedFile>
+    where
+        P: Into<String>,
+    {
+        Ok(GeneratedFile::new(path, self.render()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract_stylesheet_from_text;
+    use crate::utils::escape_class_name;
+    use convert_case::{Case, Casing};
+    use std::fs::{create_dir_all, write};
+    use std::path::PathBuf;
+
+    /// Writes the template in a directory of its own, named after the test
+    fn template_path(test: &str, file_name: &str, source: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("pyaco-runtime-{}-{}", test, std::process::id()));
+
+        create_dir_all(&directory).unwrap();
+
+        let path = directory.join(file_name);
+
+        write(&path, source).unwrap();
+
+        path
+    }
+
+    fn options(output_directory: &str) -> TemplateOptions {
+        TemplateOptions {
+            source_root: Some(PathBuf::from("src")),
+            ..TemplateOptions::new(output_directory, "Css")
+        }
+    }
+
+    #[test]
+    fn it_renders_the_classes_and_module_name() {
+        let path = template_path(
+            "render",
+            "Css.svelte",
+            "{{ module_name }}{% for class in classes %} {{ class }}{% endfor %}",
+        );
+
+        let classes = extract_stylesheet_from_text(".p-2 {} .m-4 {}")
+            .unwrap()
+            .classes;
+
+        let options = options("src/Styles");
+
+        let template = RuntimeTemplate::new(&path, &options, &classes).unwrap();
+
+        assert_eq!(template.extension(), "svelte");
+        assert_eq!(template.render().unwrap(), "Styles.Css m-4 p-2");
+    }
+
+    #[test]
+    fn it_names_the_classes_like_the_built_in_generators() {
+        let path = template_path(
+            "name",
+            "Css.ts",
+            "{% for class in classes %}{{ class|name }} {% endfor %}",
+        );
+
+        let css = r".w-1\/2 {} .-mt-2 {} .\!p-2 {} .md\:hover\:bg-red-500 {} .\32xl {}";
+
+        let classes = extract_stylesheet_from_text(css).unwrap().classes;
+
+        let options = options("src");
+
+        let template = RuntimeTemplate::new(&path, &options, &classes).unwrap();
+
+        let expected = classes
+            .keys()
+            .map(|class| format!("{} ", escape_class_name(class).to_case(Case::Camel)))
+            .collect::<String>();
+
+        assert_eq!(template.render().unwrap(), expected);
+
+        let classes = extract_stylesheet_from_text(".tw-p-2 {} .md\\:tw-m-4 {}")
+            .unwrap()
+            .classes;
+
+        let options = TemplateOptions {
+            class_prefix: "tw-".to_string(),
+            ..options
+        };
+
+        let template = RuntimeTemplate::new(&path, &options, &classes).unwrap();
+
+        assert_eq!(template.render().unwrap(), "mdM4 p2 ");
+    }
+
+    #[test]
+    fn it_requires_a_template_extension() {
+        let path = template_path("extension", "Css", "{{ module_name }}");
+
+        let classes = extract_stylesheet_from_text(".p-2 {}").unwrap().classes;
+
+        let options = options("src");
+
+        let error = RuntimeTemplate::new(&path, &options, &classes)
+            .err()
+            .unwrap()
+            .to_string();
+
+        assert!(error.contains("has no extension"), "{}", error);
+    }
+}
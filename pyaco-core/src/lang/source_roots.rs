@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Deserialize;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+struct ElmJson {
+    #[serde(rename = "source-directories", default)]
+    source_directories: Vec<PathBuf>,
+}
+
+/// Finds the source directories declared in the current directory, in an elm.json
+/// file or in a spago config, empty if none of them exists
+pub(crate) fn detect_source_roots() -> Result<Vec<PathBuf>> {
+    if Path::new("elm.json").exists() {
+        let elm_json = serde_json::from_str::<ElmJson>(&read_to_string("elm.json")?)
+            .map_err(|error| anyhow!("couldn't read elm.json: {}", error))?;
+
+        info!(
+            "Using the elm.json source directories {:?}",
+            elm_json.source_directories
+        );
+
+        return Ok(elm_json.source_directories);
+    }
+
+    if Path::new("spago.dhall").exists() {
+        let source_roots = spago_dhall_sources(&read_to_string("spago.dhall")?);
+
+        info!("Using the spago.dhall sources {:?}", source_roots);
+
+        return Ok(source_roots);
+    }
+
+    // Spago 0.93+ (spago.yaml) doesn't allow to configure the sources location
+    if Path::new("spago.yaml").exists() {
+        return Ok(vec![PathBuf::from("src"), PathBuf::from("test")]);
+    }
+
+    Ok(Vec::new())
+}
+
+/// Extracts the directories from the `sources` globs (`"src/**/*.purs"` gives `src`)
+fn spago_dhall_sources(spago_dhall: &str) -> Vec<PathBuf> {
+    let sources = match spago_dhall
+        .find("sources")
+        .and_then(|start| spago_dhall[start..].find('[').map(|open| start + open))
+        .and_then(|open| {
+            spago_dhall[open..]
+                .find(']')
+                .map(|close| &spago_dhall[open + 1..open + close])
+        }) {
+        Some(sources) => sources,
+        None => return Vec::new(),
+    };
+
+    sources
+        .split(',')
+        .filter_map(|source| {
+            let glob = source.trim().trim_matches('"');
+
+            let directory = Path::new(glob)
+                .components()
+                .take_while(|component| !component.as_os_str().to_string_lossy().contains('*'))
+                .collect::<PathBuf>();
+
+            if directory.as_os_str().is_empty() {
+                None
+            } else {
+                Some(directory)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_extracts_spago_dhall_sources() {
+        assert_eq!(
+            spago_dhall_sources(
+                r#"{ name = "app", dependencies = [ "prelude" ], sources = [ "src/**/*.purs", "test/**/*.purs" ] }"#
+            ),
+            vec![PathBuf::from("src"), PathBuf::from("test")]
+        );
+    }
+}
@@ -1,38 +1,296 @@
 use anyhow::Result;
 use askama::Template;
-use std::collections::HashSet;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
-use super::LangTemplate;
+use super::{breakpoints, render_file, split_module_options, LangTemplate, TemplateOptions};
+use crate::split::INTERNAL_CHUNK_NAME;
+use crate::{
+    group_by_category, Aliases, Breakpoint, Category, Chunk, Classes, CustomProperties,
+    GeneratedFile, VariantApi,
+};
+
+/// How the `typescript` generator declares the classes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TypescriptStyle {
+    /// An exported constant per class (`p2`)
+    #[default]
+    Constants,
+    /// A single frozen object map (`Css.p2`)
+    Object,
+    /// A `const enum` (`Css.p2`), whose string members can't be built from any string. It can't
+    /// be used with `isolatedModules`, as required by esbuild, swc, and Vite.
+    ConstEnum,
+    /// A namespace exporting a constant per class (`Css.p2`)
+    Namespace,
+}
+
+impl FromStr for TypescriptStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "constants" => Ok(TypescriptStyle::Constants),
+            "object" => Ok(TypescriptStyle::Object),
+            "const-enum" => Ok(TypescriptStyle::ConstEnum),
+            "namespace" => Ok(TypescriptStyle::Namespace),
+            unknown => Err(format!(
+                "\"{}\" is not a valid typescript style, should be one of (constants|object|const-enum|namespace)",
+                unknown
+            )),
+        }
+    }
+}
 
 #[derive(Template)]
 #[template(path = "typescript.txt")]
 pub struct TypescriptTemplate<'a> {
-    classes: &'a HashSet<String>,
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    namespaces: BTreeMap<Category, Vec<&'a String>>,
+    variants: VariantApi,
+    breakpoints: Vec<Breakpoint>,
+    animations: &'a [String],
+    guards: bool,
+    style: TypescriptStyle,
+    aliases: &'a Aliases,
 }
 
 impl<'a> LangTemplate<'a> for TypescriptTemplate<'a> {
-    fn new(
-        _output_directory: &'a str,
-        _output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self> {
-        Ok(Self { classes })
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self> {
+        let style = options.typescript_style;
+
+        // Enum members can't be computed, so a const enum has a member per class
+        let variants = if style == TypescriptStyle::ConstEnum {
+            VariantApi::default()
+        } else {
+            variant_api(options, classes)
+        };
+
+        Ok(Self {
+            class_prefix: &options.class_prefix,
+            classes,
+            namespaces: namespaces(options, classes, &variants),
+            variants,
+            breakpoints: breakpoints(options, classes),
+            animations: &options.animations,
+            guards: options.guards,
+            style,
+            aliases: &options.aliases,
+        })
+    }
+}
+
+pub(crate) fn variant_api(options: &TemplateOptions, classes: &Classes) -> VariantApi {
+    if options.variants {
+        VariantApi::new(classes, &options.class_prefix)
+    } else {
+        VariantApi::default()
+    }
+}
+
+/// The namespaces of the classes generated as constants
+pub(crate) fn namespaces<'a>(
+    options: &TemplateOptions,
+    classes: &'a Classes,
+    variants: &VariantApi,
+) -> BTreeMap<Category, Vec<&'a String>> {
+    if !options.namespaces {
+        return BTreeMap::new();
+    }
+
+    let mut namespaces = group_by_category(classes, &options.class_prefix);
+
+    for classes in namespaces.values_mut() {
+        classes.retain(|class| !variants.is_covered(class));
+    }
+
+    namespaces.retain(|_, classes| !classes.is_empty());
+
+    namespaces
+}
+
+#[derive(Template)]
+#[template(path = "typescript_internal.txt")]
+struct TypescriptInternalTemplate;
+
+#[derive(Template)]
+#[template(path = "typescript_chunk.txt")]
+struct TypescriptChunkTemplate<'a> {
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    internal_module_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "typescript_aggregator.txt")]
+struct TypescriptAggregatorTemplate<'a> {
+    directory: &'a str,
+    internal_module_name: &'a str,
+    chunk_module_names: Vec<&'a str>,
+}
+
+/// See [`super::generate_split`]
+pub fn generate_split(options: &TemplateOptions, chunks: &[Chunk]) -> Result<Vec<GeneratedFile>> {
+    let mut files = vec![render_file(
+        &split_module_options(options, INTERNAL_CHUNK_NAME),
+        &TypescriptInternalTemplate,
+        "ts",
+    )?];
+
+    for chunk in chunks {
+        files.push(render_file(
+            &split_module_options(options, &chunk.name),
+            &TypescriptChunkTemplate {
+                class_prefix: &options.class_prefix,
+                classes: &chunk.classes,
+                internal_module_name: INTERNAL_CHUNK_NAME,
+            },
+            "ts",
+        )?);
     }
+
+    files.push(render_file(
+        options,
+        &TypescriptAggregatorTemplate {
+            directory: &options.output_filename,
+            internal_module_name: INTERNAL_CHUNK_NAME,
+            chunk_module_names: chunks.iter().map(|chunk| chunk.name.as_str()).collect(),
+        },
+        "ts",
+    )?);
+
+    Ok(files)
 }
 
-mod filters {
+#[derive(Template)]
+#[template(path = "typescript_tokens.txt")]
+struct TypescriptTokensTemplate<'a> {
+    custom_properties: &'a CustomProperties,
+}
+
+/// See [`super::generate_tokens`], shared by the TypeScript generators
+pub fn generate_tokens(
+    options: &TemplateOptions,
+    custom_properties: &CustomProperties,
+) -> Result<Vec<GeneratedFile>> {
+    Ok(vec![render_file(
+        options,
+        &TypescriptTokensTemplate { custom_properties },
+        "ts",
+    )?])
+}
+
+pub(crate) mod filters {
     use askama::Result;
     use convert_case::{Case, Casing};
 
-    use crate::utils::escape_class_name;
+    use crate::utils::{
+        alias_doc_lines, block_doc_comment, class_jsdoc_lines, custom_property_doc_lines,
+        escape_class_name, escape_keyword, indent_doc_comment, string_literal, strip_class_prefix,
+    };
+    use crate::{ClassInfo, Classes, CustomProperty};
 
-    pub fn name(class: &str) -> Result<String> {
-        let name = escape_class_name(class).to_case(Case::Camel);
+    /// The reserved words of JavaScript (strict mode included), which the classes and variants
+    /// can't be named after
+    const KEYWORDS: &[&str] = &[
+        "arguments",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "eval",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "function",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "var",
+        "void",
+        "while",
+        "with",
+        "yield",
+    ];
 
-        // TODO: Escape more keywords
-        Ok(match name.as_str() {
-            "static" => String::from("static_"),
-            _ => name,
-        })
+    /// The identifier of a class, without the Tailwind prefix (`tw-`) of the stylesheet
+    pub fn name(class: &str, prefix: &str) -> Result<String> {
+        Ok(escape_keyword(
+            escape_class_name(&strip_class_prefix(class, prefix)).to_case(Case::Camel),
+            KEYWORDS,
+        ))
+    }
+
+    pub fn doc(info: &ClassInfo) -> Result<String> {
+        Ok(block_doc_comment(&class_jsdoc_lines(info)))
+    }
+
+    /// The doc comment of a class declared as an object, enum, or namespace member
+    pub fn member_doc(info: &ClassInfo) -> Result<String> {
+        Ok(indent_doc_comment(&block_doc_comment(&class_jsdoc_lines(
+            info,
+        ))))
+    }
+
+    /// The doc comment of a class declared as a member of its namespace, which keeps its
+    /// deprecation
+    pub fn namespace_member_doc(class: &str, classes: &Classes) -> Result<String> {
+        classes
+            .get(class)
+            .map_or_else(|| Ok(String::new()), member_doc)
+    }
+
+    pub fn alias_doc(members: &[String]) -> Result<String> {
+        Ok(block_doc_comment(&alias_doc_lines(members)))
+    }
+
+    /// The doc comment of an alias declared as an object, enum, or namespace member
+    pub fn alias_member_doc(members: &[String]) -> Result<String> {
+        Ok(indent_doc_comment(&block_doc_comment(&alias_doc_lines(
+            members,
+        ))))
+    }
+
+    pub fn token_doc(custom_property: &CustomProperty) -> Result<String> {
+        Ok(block_doc_comment(&custom_property_doc_lines(
+            custom_property,
+        )))
+    }
+
+    pub fn string(value: &str) -> Result<String> {
+        Ok(string_literal(value))
     }
 }
@@ -1,21 +1,66 @@
 use anyhow::Result;
 use askama::Template;
-use std::collections::HashSet;
 
-use super::LangTemplate;
+use super::{breakpoints, render_file, split_module_options, LangTemplate, TemplateOptions};
+use crate::{Aliases, Breakpoint, Chunk, Classes, GeneratedFile};
 
 #[derive(Template)]
 #[template(path = "typescript_type_2.txt")]
 pub struct TypescriptType2Template<'a> {
-    classes: &'a HashSet<String>,
+    class_prefix: &'a str,
+    classes: &'a Classes,
+    breakpoints: Vec<Breakpoint>,
+    animations: &'a [String],
+    guards: bool,
+    aliases: &'a Aliases,
 }
 
 impl<'a> LangTemplate<'a> for TypescriptType2Template<'a> {
-    fn new(
-        _output_directory: &'a str,
-        _output_filename: &'a str,
-        classes: &'a HashSet<String>,
-    ) -> Result<Self> {
-        Ok(Self { classes })
+    fn new(options: &'a TemplateOptions, classes: &'a Classes) -> Result<Self> {
+        Ok(Self {
+            class_prefix: &options.class_prefix,
+            classes,
+            breakpoints: breakpoints(options, classes),
+            animations: &options.animations,
+            guards: options.guards,
+            aliases: &options.aliases,
+        })
     }
 }
+
+#[derive(Template)]
+#[template(path = "typescript_type_2_aggregator.txt")]
+struct TypescriptType2AggregatorTemplate<'a> {
+    directory: &'a str,
+    chunk_module_names: Vec<&'a str>,
+}
+
+/// See [`super::generate_split`], the aggregator checks the classes against each chunk type
+pub fn generate_split(options: &TemplateOptions, chunks: &[Chunk]) -> Result<Vec<GeneratedFile>> {
+    let mut files = Vec::with_capacity(chunks.len() + 1);
+
+    for chunk in chunks {
+        files.push(render_file(
+            &split_module_options(options, &chunk.name),
+            &super::typescript_type_1::TypescriptTypeChunkTemplate {
+                classes: &chunk.classes,
+            },
+            "ts",
+        )?);
+    }
+
+    files.push(render_file(
+        options,
+        &TypescriptType2AggregatorTemplate {
+            directory: &options.output_filename,
+            chunk_module_names: chunks.iter().map(|chunk| chunk.name.as_str()).collect(),
+        },
+        "ts",
+    )?);
+
+    Ok(files)
+}
+
+mod filters {
+    pub use super::super::typescript::filters::{alias_doc, name};
+}
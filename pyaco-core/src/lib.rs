@@ -1,20 +1,86 @@
 use anyhow::{anyhow, Result};
 use cssparser::{Parser, ParserInput, RuleListParser};
 use log::{error, info};
-use std::collections::HashSet;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
 use std::ffi::OsStr;
-use std::io::Read;
+use std::fmt::{self, Display};
+use std::fs::{read_to_string, remove_file, rename};
+use std::io::{ErrorKind, Read, Write};
 use std::path::PathBuf;
+use std::process;
 use std::{fs::File, path::Path};
 use url::Url;
 
-use crate::classes_parser::ClassesParser;
+use crate::animations::extract_animations;
+use crate::classes_parser::{ClassesParser, CssRule, Rule};
+use crate::custom_properties::extract_custom_properties;
+use crate::deprecations::find_annotations;
+use crate::utils::split_variants;
 
+pub use aliases::*;
+pub use animations::*;
+pub use breakpoints::*;
+pub use category::*;
+pub use config::*;
+pub use css_modules::*;
+pub use custom_properties::*;
+pub use deprecations::*;
+pub use filter::*;
 pub use lang::*;
+pub use manifest::*;
+pub use prefix::*;
+pub use split::*;
+pub use variants::*;
 
+mod aliases;
+mod animations;
+mod breakpoints;
+mod category;
 mod classes_parser;
+mod config;
+mod css_modules;
+mod custom_properties;
+mod deprecations;
+mod filter;
 mod lang;
+mod manifest;
+mod prefix;
+mod split;
 mod utils;
+mod variants;
+
+/// A css declaration (`property: value`) found in a rule block
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Declaration {
+    pub property: String,
+    pub value: String,
+}
+
+/// What is known about a class once extracted from the css
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ClassInfo {
+    /// Declarations of the rules this class is the subject of
+    pub declarations: Vec<Declaration>,
+    /// Media queries this class is defined in, empty if defined at the top level only
+    pub media: Vec<String>,
+    /// Variant prefixes of the class, outermost first (`["md", "hover"]` for `md:hover:bg-red-500`)
+    pub variants: Vec<String>,
+    /// Set when the class is annotated as deprecated in the css, or in the configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecation>,
+}
+
+/// All the classes extracted from a css source, sorted by name
+pub type Classes = BTreeMap<String, ClassInfo>;
+
+/// Everything extracted from a css source
+#[derive(Debug, Default)]
+pub struct Stylesheet {
+    pub classes: Classes,
+    pub custom_properties: CustomProperties,
+    pub animations: Animations,
+}
 
 #[derive(Debug)]
 pub enum InputType {
@@ -22,6 +88,15 @@ pub enum InputType {
     Url(Url),
 }
 
+impl Display for InputType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
 impl InputType {
     pub fn from_path<S: AsRef<str>>(input: S) -> Self {
         match Url::parse(input.as_ref()) {
@@ -30,15 +105,65 @@ impl InputType {
         }
     }
 
-    pub fn extract_classes(&self) -> Result<HashSet<String>> {
+    pub fn extract(&self) -> Result<Classes> {
+        Ok(self.extract_stylesheet()?.classes)
+    }
+
+    /// Extracts the classes, the custom properties, and the animations
+    pub fn extract_stylesheet(&self) -> Result<Stylesheet> {
         match self {
-            Self::Path(path) => extract_classes_from_file(path),
-            Self::Url(url) => extract_classes_from_url(url),
+            Self::Path(path) => extract_stylesheet_from_file(path),
+            Self::Url(url) => extract_stylesheet_from_url(url),
         }
     }
+
+    /// Extracts the stylesheet, keeping only the classes accepted by the filter
+    pub fn extract_filtered_stylesheet(&self, filter: &ClassFilter) -> Result<Stylesheet> {
+        let mut stylesheet = self.extract_stylesheet()?;
+
+        filter.apply(&mut stylesheet.classes)?;
+
+        Ok(stylesheet)
+    }
+
+    pub fn extract_classes(&self, filter: &ClassFilter) -> Result<HashSet<String>> {
+        Ok(self
+            .extract_filtered_stylesheet(filter)?
+            .classes
+            .into_keys()
+            .collect())
+    }
 }
 
 pub fn extract_classes_from_file<P>(path: P) -> Result<HashSet<String>>
+where
+    P: AsRef<Path>,
+{
+    Ok(extract_from_file(path)?.into_keys().collect())
+}
+
+pub fn extract_classes_from_url<U>(url: U) -> Result<HashSet<String>>
+where
+    U: AsRef<str>,
+{
+    Ok(extract_from_url(url)?.into_keys().collect())
+}
+
+pub fn extract_from_file<P>(path: P) -> Result<Classes>
+where
+    P: AsRef<Path>,
+{
+    Ok(extract_stylesheet_from_file(path)?.classes)
+}
+
+pub fn extract_from_url<U>(url: U) -> Result<Classes>
+where
+    U: AsRef<str>,
+{
+    Ok(extract_stylesheet_from_url(url)?.classes)
+}
+
+pub fn extract_stylesheet_from_file<P>(path: P) -> Result<Stylesheet>
 where
     P: AsRef<Path>,
 {
@@ -48,48 +173,116 @@ where
 
     file.read_to_string(&mut file_content)?;
 
-    extract_classes_from_text(file_content)
+    extract_stylesheet_from_text(file_content)
 }
 
-pub fn extract_classes_from_url<U>(url: U) -> Result<HashSet<String>>
+pub fn extract_stylesheet_from_url<U>(url: U) -> Result<Stylesheet>
 where
     U: AsRef<str>,
 {
     let css_text = ureq::get(url.as_ref()).call()?.into_string()?;
 
-    extract_classes_from_text(css_text)
+    extract_stylesheet_from_text(css_text)
 }
 
-fn extract_classes_from_text<C>(css_text: C) -> Result<HashSet<String>>
+pub(crate) fn extract_stylesheet_from_text<C>(css_text: C) -> Result<Stylesheet>
 where
     C: AsRef<str>,
 {
-    let mut parser_input = ParserInput::new(css_text.as_ref());
-
-    let mut parser = Parser::new(&mut parser_input);
+    let (rules, keyframes) = parse_rules(css_text.as_ref(), false);
 
-    let rule_list_parser = RuleListParser::new_for_stylesheet(&mut parser, ClassesParser);
+    let custom_properties = extract_custom_properties(&rules);
 
-    let out_classes =
-        rule_list_parser
-            .into_iter()
-            .fold(HashSet::new(), |mut classes, classes_results| {
-                match classes_results {
-                    Ok(None) => (),
-                    Ok(Some(new_classes)) => classes.extend(new_classes),
-                    Err(error) => error!("An error occured while parsing the css: {:?}", error),
-                };
+    let out_classes = extract_classes_from_rules(rules);
 
-                classes
-            });
+    let animations = extract_animations(keyframes, &out_classes);
 
     if out_classes.is_empty() {
         return Err(anyhow!("no css classes found, are you sure the provided css source contains at least one class and is valid?"));
     }
 
-    info!("{} classes found", out_classes.len());
+    info!(
+        "{} classes, {} custom properties, and {} animations found",
+        out_classes.len(),
+        custom_properties.len(),
+        animations.len()
+    );
 
-    Ok(out_classes)
+    Ok(Stylesheet {
+        classes: out_classes,
+        custom_properties,
+        animations,
+    })
+}
+
+/// Parses the style rules and the `@keyframes` names of a stylesheet, in CSS Modules mode only
+/// the local classes are kept (see [`CssModule`])
+pub(crate) fn parse_rules(css_text: &str, css_modules: bool) -> (Vec<Rule>, Vec<String>) {
+    let annotations = find_annotations(css_text);
+
+    let mut parser_input = ParserInput::new(css_text);
+
+    let mut parser = Parser::new(&mut parser_input);
+
+    let rule_list_parser = RuleListParser::new_for_stylesheet(
+        &mut parser,
+        ClassesParser::new(annotations, css_modules),
+    );
+
+    let mut rules = Vec::new();
+
+    let mut keyframes = Vec::new();
+
+    for rules_result in rule_list_parser {
+        match rules_result {
+            Ok(new_rules) => {
+                for rule in new_rules {
+                    match rule {
+                        CssRule::Style(rule) => rules.push(rule),
+                        CssRule::Keyframes(name) => keyframes.push(name),
+                    }
+                }
+            }
+            Err(error) => error!("An error occured while parsing the css: {:?}", error),
+        };
+    }
+
+    (rules, keyframes)
+}
+
+pub(crate) fn extract_classes_from_rules(rules: Vec<Rule>) -> Classes {
+    let mut classes = Classes::new();
+
+    for rule in rules {
+        for class in rule.classes {
+            let info = classes.entry(class).or_insert_with_key(|class| ClassInfo {
+                variants: split_variants(class)
+                    .0
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                ..Default::default()
+            });
+
+            for query in &rule.media {
+                if !info.media.contains(query) {
+                    info.media.push(query.clone());
+                }
+            }
+        }
+
+        for class in rule.subjects {
+            if let Some(info) = classes.get_mut(&class) {
+                info.declarations.extend(rule.declarations.iter().cloned());
+
+                if let Some(note) = &rule.deprecation {
+                    info.deprecated = Some(Deprecation::new(note.as_str()));
+                }
+            }
+        }
+    }
+
+    classes
 }
 
 pub fn resolve_path<D, P>(directory: D, filename: P, extension: &str) -> Result<String>
@@ -103,3 +296,188 @@ where
 
     Ok(format!("{}.{}", output_path, extension))
 }
+
+/// Generated code, rendered in memory and not written yet
+#[derive(Debug)]
+pub struct GeneratedFile {
+    pub path: String,
+    pub code: String,
+}
+
+impl GeneratedFile {
+    pub fn new<P>(path: P, code: String) -> Self
+    where
+        P: Into<String>,
+    {
+        GeneratedFile {
+            path: path.into(),
+            code,
+        }
+    }
+
+    /// Writes the code, unless the file on disk already contains it.
+    ///
+    /// The code is written into a temporary file that is then renamed, so that
+    /// a partially written file is never visible (by bundlers, watchers, etc...).
+    pub fn write(&self) -> Result<()> {
+        if self.stale_content()?.is_none() {
+            info!("{} is up to date, skipping", self.path);
+
+            return Ok(());
+        }
+
+        info!("Writing code into {}", self.path);
+
+        let path = Path::new(&self.path);
+
+        let temp_path = path.with_file_name(format!(
+            ".{}.{}.tmp",
+            path.file_name()
+                .map(|file_name| file_name.to_string_lossy())
+                .unwrap_or_default(),
+            process::id()
+        ));
+
+        let result = File::create(&temp_path)
+            .and_then(|mut output| output.write_all(self.code.as_bytes()))
+            .and_then(|_| rename(&temp_path, path));
+
+        if result.is_err() {
+            // Best effort cleanup, the original error is more relevant
+            let _ = remove_file(&temp_path);
+        }
+
+        Ok(result?)
+    }
+
+    /// Returns the content currently written on disk if it differs from the generated code,
+    /// `Some("")` is returned when the file doesn't exist yet
+    pub fn stale_content(&self) -> Result<Option<String>> {
+        let current_code = match read_to_string(&self.path) {
+            Ok(current_code) => current_code,
+            Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        if current_code == self.code {
+            return Ok(None);
+        }
+
+        Ok(Some(current_code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_stale_content() {
+        let directory = std::env::temp_dir().join(format!("pyaco-stale-content-{}", process::id()));
+
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("css.ts").to_string_lossy().into_owned();
+
+        let file = GeneratedFile::new(&path, "export {};\n".to_string());
+
+        // A missing file is stale, with an empty content
+        assert_eq!(file.stale_content().unwrap(), Some(String::new()));
+
+        std::fs::write(&path, "export const p2 = 1;\n").unwrap();
+
+        assert_eq!(
+            file.stale_content().unwrap(),
+            Some("export const p2 = 1;\n".to_string())
+        );
+
+        file.write().unwrap();
+
+        assert_eq!(file.stale_content().unwrap(), None);
+    }
+
+    #[test]
+    fn it_keeps_declarations_and_media_queries() {
+        let classes = extract_stylesheet_from_text(
+            ".p-2 { padding: 0.5rem } \
+             .group:hover .group-hover\\:flex { display: flex } \
+             @media (min-width: 768px) { .md\\:p-2 { padding: 0.5rem !important } }",
+        )
+        .unwrap()
+        .classes;
+
+        assert_eq!(
+            classes.keys().collect::<Vec<_>>(),
+            vec!["group", "group-hover:flex", "md:p-2", "p-2"]
+        );
+
+        assert!(classes["group"].declarations.is_empty());
+
+        assert_eq!(
+            classes["group-hover:flex"].declarations,
+            vec![Declaration {
+                property: "display".to_string(),
+                value: "flex".to_string()
+            }]
+        );
+
+        assert_eq!(classes["md:p-2"].declarations[0].value, "0.5rem !important");
+        assert_eq!(classes["md:p-2"].media, vec!["(min-width: 768px)"]);
+        assert!(classes["p-2"].media.is_empty());
+    }
+
+    #[test]
+    fn it_collects_keyframes() {
+        let stylesheet = extract_stylesheet_from_text(
+            "@keyframes spin { to { transform: rotate(360deg) } } \
+             @-webkit-keyframes \"fade-in\" { from { opacity: 0 } } \
+             @media (prefers-reduced-motion: no-preference) { .animate-spin { animation: spin 1s linear infinite } }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            stylesheet.animations.keys().collect::<Vec<_>>(),
+            ["fade-in", "spin"]
+        );
+        assert_eq!(stylesheet.animations["spin"].classes, ["animate-spin"]);
+
+        // Keyframes selectors are not classes
+        assert_eq!(
+            stylesheet.classes.keys().collect::<Vec<_>>(),
+            ["animate-spin"]
+        );
+    }
+
+    #[test]
+    fn it_only_writes_changed_files() {
+        let directory = std::env::temp_dir().join(format!("pyaco-{}", process::id()));
+
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("Css.elm").to_string_lossy().into_owned();
+
+        let file = GeneratedFile::new(path.as_str(), "module Css".to_string());
+
+        file.write().unwrap();
+
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+
+        file.write().unwrap();
+
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().modified().unwrap(),
+            modified
+        );
+
+        GeneratedFile::new(path.as_str(), "module Css2".to_string())
+            .write()
+            .unwrap();
+
+        assert_eq!(read_to_string(&path).unwrap(), "module Css2");
+
+        // Only the generated file is left, no temporary files
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
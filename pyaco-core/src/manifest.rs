@@ -0,0 +1,148 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::utils::{class_identifier, strip_class_prefix};
+use crate::{Category, Classes, Declaration, Deprecation, GeneratedFile};
+
+#[derive(Serialize)]
+struct ManifestClass<'a> {
+    name: &'a str,
+    identifier: String,
+    source: &'a str,
+    category: Category,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    declarations: &'a Vec<Declaration>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    media: &'a Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    variants: &'a Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecated: &'a Option<Deprecation>,
+}
+
+/// A machine-readable description of the extracted classes, meant to be consumed
+/// by tools that can't link against pyaco
+#[derive(Serialize)]
+pub struct Manifest<'a> {
+    source: &'a str,
+    classes: Vec<ManifestClass<'a>>,
+}
+
+impl<'a> Manifest<'a> {
+    /// The identifiers and categories leave out the Tailwind `class_prefix` (`tw-`)
+    pub fn new(source: &'a str, classes: &'a Classes, class_prefix: &str) -> Self {
+        let classes = classes
+            .iter()
+            .map(|(name, info)| ManifestClass {
+                name,
+                identifier: class_identifier(&strip_class_prefix(name, class_prefix)),
+                source,
+                category: Category::classify(&strip_class_prefix(name, class_prefix), info),
+                declarations: &info.declarations,
+                media: &info.media,
+                variants: &info.variants,
+                deprecated: &info.deprecated,
+            })
+            .collect();
+
+        Manifest { source, classes }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn generate<P>(&self, path: P) -> Result<GeneratedFile>
+    where
+        P: Into<String>,
+    {
+        Ok(GeneratedFile::new(path, self.to_json()?))
+    }
+}
+
+/// A JSON Schema that only accepts the extracted class names
+pub struct JsonSchema<'a> {
+    classes: &'a Classes,
+}
+
+impl<'a> JsonSchema<'a> {
+    pub fn new(classes: &'a Classes) -> Self {
+        JsonSchema { classes }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "CssClass",
+            "type": "string",
+            "enum": self.classes.keys().collect::<Vec<_>>(),
+        });
+
+        Ok(serde_json::to_string_pretty(&schema)?)
+    }
+
+    pub fn generate<P>(&self, path: P) -> Result<GeneratedFile>
+    where
+        P: Into<String>,
+    {
+        Ok(GeneratedFile::new(path, self.to_json()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClassInfo;
+
+    fn classes() -> Classes {
+        let mut classes = Classes::new();
+
+        classes.insert(
+            "p-2".to_string(),
+            ClassInfo {
+                declarations: vec![Declaration {
+                    property: "padding".to_string(),
+                    value: "0.5rem".to_string(),
+                }],
+                media: Vec::new(),
+                ..Default::default()
+            },
+        );
+
+        classes.insert("md:flex".to_string(), ClassInfo::default());
+
+        classes
+    }
+
+    #[test]
+    fn it_lists_classes_with_their_metadata() {
+        let classes = classes();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&Manifest::new("styles.css", &classes, "").to_json().unwrap())
+                .unwrap();
+
+        assert_eq!(manifest["source"], "styles.css");
+        assert_eq!(manifest["classes"][0]["name"], "md:flex");
+        assert_eq!(manifest["classes"][0]["identifier"], "mdFlex");
+        assert!(manifest["classes"][0].get("declarations").is_none());
+        assert_eq!(manifest["classes"][1]["identifier"], "p2");
+        assert_eq!(manifest["classes"][1]["category"], "Spacing");
+        assert_eq!(
+            manifest["classes"][1]["declarations"][0]["property"],
+            "padding"
+        );
+    }
+
+    #[test]
+    fn it_generates_an_enum_schema() {
+        let classes = classes();
+
+        let schema: serde_json::Value =
+            serde_json::from_str(&JsonSchema::new(&classes).to_json().unwrap()).unwrap();
+
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["enum"], json!(["md:flex", "p-2"]));
+    }
+}
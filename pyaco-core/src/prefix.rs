@@ -0,0 +1,56 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+use crate::utils::{class_identifier, strip_class_prefix};
+use crate::Classes;
+
+/// Checks that leaving out the Tailwind prefix (`tw-`) doesn't give two classes the same
+/// identifier, like `p-2` and `tw-p-2` which would both be declared as `p2`
+pub fn validate_class_prefix(classes: &Classes, prefix: &str) -> Result<()> {
+    if prefix.is_empty() {
+        return Ok(());
+    }
+
+    let mut identifiers: HashMap<String, &str> = HashMap::new();
+
+    for class in classes.keys() {
+        let identifier = class_identifier(&strip_class_prefix(class, prefix));
+
+        if let Some(other) = identifiers.insert(identifier, class) {
+            return Err(anyhow!(
+                "{} and {} have the same identifier once the {} prefix is left out, exclude one of them",
+                other,
+                class,
+                prefix
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClassInfo;
+
+    fn classes(names: &[&str]) -> Classes {
+        names
+            .iter()
+            .map(|name| (name.to_string(), ClassInfo::default()))
+            .collect()
+    }
+
+    #[test]
+    fn it_rejects_the_classes_colliding_without_the_prefix() {
+        assert!(validate_class_prefix(&classes(&["tw-p-2", "md:tw-p-2", "tw-m-4"]), "tw-").is_ok());
+        assert!(validate_class_prefix(&classes(&["p-2", "tw-p-2"]), "").is_ok());
+
+        let error = validate_class_prefix(&classes(&["p-2", "tw-p-2"]), "tw-").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "p-2 and tw-p-2 have the same identifier once the tw- prefix is left out, exclude one of them"
+        );
+    }
+}
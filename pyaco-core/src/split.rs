@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::category::Category;
+use crate::lang::validate_module_name;
+use crate::utils::{class_identifier, strip_class_prefix, utility_name};
+use crate::{ClassInfo, Classes};
+
+/// The name of the module shared by the chunks, that no chunk can use
+pub(crate) const INTERNAL_CHUNK_NAME: &str = "Internal";
+
+/// How the classes are grouped when the generated code is split
+#[derive(Clone, Copy, Debug)]
+pub enum SplitBy {
+    /// The first segment of the utility name (`bg` for `hover:bg-red-500`)
+    Prefix,
+    /// The utility category (`Color` for `hover:bg-red-500`)
+    Category,
+}
+
+impl FromStr for SplitBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prefix" => Ok(SplitBy::Prefix),
+            "category" => Ok(SplitBy::Category),
+            unknown => Err(format!(
+                "\"{}\" is not a valid split strategy, should be one of (prefix|category)",
+                unknown
+            )),
+        }
+    }
+}
+
+/// A group of classes generated in its own module
+#[derive(Debug)]
+pub struct Chunk {
+    /// A capitalized identifier, usable as a module name
+    pub name: String,
+    pub classes: Classes,
+}
+
+/// Groups the classes into chunks of at most `max_classes` classes, chunks too big
+/// for a single group are numbered (`Spacing1`, `Spacing2`, ...). The Tailwind `class_prefix`
+/// (`tw-`) is ignored when grouping.
+pub fn split_classes(
+    classes: &Classes,
+    split_by: SplitBy,
+    max_classes: usize,
+    class_prefix: &str,
+) -> Vec<Chunk> {
+    let max_classes = max_classes.max(1);
+
+    let mut groups: BTreeMap<String, Classes> = BTreeMap::new();
+
+    for (class, info) in classes {
+        groups
+            .entry(group_name(
+                &strip_class_prefix(class, class_prefix),
+                info,
+                split_by,
+            ))
+            .or_default()
+            .insert(class.clone(), info.clone());
+    }
+
+    let mut chunks = Vec::with_capacity(groups.len());
+
+    for (name, classes) in groups {
+        if classes.len() <= max_classes {
+            chunks.push(Chunk { name, classes });
+
+            continue;
+        }
+
+        let mut classes = classes.into_iter().peekable();
+
+        let mut index = 1;
+
+        while classes.peek().is_some() {
+            chunks.push(Chunk {
+                name: format!("{}{}", name, index),
+                classes: classes.by_ref().take(max_classes).collect(),
+            });
+
+            index += 1;
+        }
+    }
+
+    chunks
+}
+
+fn group_name(class: &str, info: &ClassInfo, split_by: SplitBy) -> String {
+    let name = match split_by {
+        SplitBy::Category => Category::classify(class, info).name().to_string(),
+        SplitBy::Prefix => {
+            let utility = utility_name(class);
+
+            let prefix = utility.split('-').next().unwrap_or(utility);
+
+            let mut name = class_identifier(prefix);
+
+            if let Some(first) = name.get_mut(..1) {
+                first.make_ascii_uppercase();
+            }
+
+            // The escaping leaves the non ASCII letters, which are not valid in module names
+            if validate_module_name(&name).is_ok() {
+                name
+            } else {
+                "Other".to_string()
+            }
+        }
+    };
+
+    if name == INTERNAL_CHUNK_NAME {
+        format!("{}Classes", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classes(names: &[&str]) -> Classes {
+        names
+            .iter()
+            .map(|name| (name.to_string(), ClassInfo::default()))
+            .collect()
+    }
+
+    fn chunk_names(chunks: &[Chunk]) -> Vec<&str> {
+        chunks.iter().map(|chunk| chunk.name.as_str()).collect()
+    }
+
+    #[test]
+    fn it_splits_classes_by_prefix() {
+        let classes = classes(&["p-2", "md:p-4", "-mt-2", "bg-red-500", "internal"]);
+
+        let chunks = split_classes(&classes, SplitBy::Prefix, 100, "");
+
+        assert_eq!(chunk_names(&chunks), ["Bg", "InternalClasses", "Mt", "P"]);
+        assert_eq!(chunks[3].classes.len(), 2);
+    }
+
+    #[test]
+    fn it_names_the_chunks_of_arbitrary_properties() {
+        let classes = classes(&["[mask-type:alpha]", "md:[--gap:1rem]", "w-[10px]", "été"]);
+
+        let chunks = split_classes(&classes, SplitBy::Prefix, 100, "");
+
+        assert_eq!(chunk_names(&chunks), ["Mask", "Other", "W"]);
+        assert_eq!(chunks[1].classes.len(), 2);
+    }
+
+    #[test]
+    fn it_splits_classes_by_category() {
+        let classes = classes(&["p-2", "mt-2", "bg-red-500", "w-1/2"]);
+
+        let chunks = split_classes(&classes, SplitBy::Category, 100, "");
+
+        assert_eq!(chunk_names(&chunks), ["Color", "Sizing", "Spacing"]);
+    }
+
+    #[test]
+    fn it_numbers_chunks_too_big() {
+        let classes = classes(&["p-1", "p-2", "p-3", "p-4", "p-5"]);
+
+        let chunks = split_classes(&classes, SplitBy::Prefix, 2, "");
+
+        assert_eq!(chunk_names(&chunks), ["P1", "P2", "P3"]);
+        assert_eq!(chunks[2].classes.len(), 1);
+    }
+}
@@ -1,5 +1,8 @@
+use convert_case::{Case, Casing};
 use std::borrow::Cow;
 
+use crate::{ClassInfo, CustomProperty};
+
 macro_rules! replace_first_char {
     ($escaped_class_name:ident, $($char:literal => $replace_with:expr),*) => (
         match $escaped_class_name.chars().nth(0) {
@@ -12,8 +15,8 @@ macro_rules! replace_first_char {
 /// Escapes any class name special characters if needed
 /// (a pointer to the provided class is returned otherwise).
 ///
-/// Performs "smart conversion" when possible (`/` to `_over_`, etc...),
-/// and returns a camel_cased string.
+/// Performs "smart conversion" when possible (`/` to `_over_`, the important modifier `!`
+/// to `important_`, etc...), and returns a camel_cased string.
 pub fn escape_class_name(class: &str) -> Cow<'_, str> {
     let mut class_chars = class.chars();
 
@@ -26,7 +29,21 @@ pub fn escape_class_name(class: &str) -> Cow<'_, str> {
         return class.into();
     }
 
-    let mut class = class.to_string();
+    // The important modifier follows the variants, and precedes the negative sign
+    let mut class = class
+        .replace(":!-", ":important_neg_")
+        .replace(":!", ":important_");
+
+    if let Some(rest) = class.strip_prefix("!-") {
+        class = format!("important_neg_{}", rest);
+    } else if let Some(rest) = class.strip_prefix('!') {
+        class = format!("important_{}", rest);
+    }
+
+    // Arbitrary properties (`[mask-type:alpha]`) are named after their content
+    if let Some(rest) = class.strip_prefix('[') {
+        class = rest.to_string();
+    }
 
     replace_first_char!(class,
         '-' => "neg_",
@@ -47,17 +64,287 @@ pub fn escape_class_name(class: &str) -> Cow<'_, str> {
         .replace("/", "_over_")
         .replace(".", "_dot_")
         .replace(
-            &['!', ':', '~', '@', '#', '$', '^', '=', '*', '(', ')', ';'][..],
+            &[
+                '!', ':', '~', '@', '#', '$', '^', '=', '*', '(', ')', ';', '[', ']', '&', '>',
+                '<', '+', '%', ',', '\'', '"', '?', '|', '{', '}',
+            ][..],
             "_",
         )
         .to_lowercase()
         .into()
 }
 
+/// The camel cased identifier used for a class in the generated code
+pub fn class_identifier(class: &str) -> String {
+    escape_class_name(class).to_case(Case::Camel)
+}
+
+/// Appends an underscore to an identifier that is a keyword of the generated language:
+/// `in` gives `in_`
+pub fn escape_keyword(identifier: String, keywords: &[&str]) -> String {
+    if keywords.contains(&identifier.as_str()) {
+        identifier + "_"
+    } else {
+        identifier
+    }
+}
+
+/// Removes the Tailwind `prefix` (`tw-`) of a class name, which follows its variants, important
+/// modifier, and negative sign: `md:!-tw-mt-2` gives `md:!-mt-2`. Classes without it are kept as is.
+pub fn strip_class_prefix<'a>(class: &'a str, prefix: &str) -> Cow<'a, str> {
+    if prefix.is_empty() {
+        return class.into();
+    }
+
+    let utility = split_variants(class).1;
+
+    let modifiers = utility.len()
+        - utility
+            .trim_start_matches('!')
+            .trim_start_matches('-')
+            .len();
+
+    match utility[modifiers..].strip_prefix(prefix) {
+        Some(rest) if !rest.is_empty() => format!(
+            "{}{}",
+            &class[..class.len() - utility.len() + modifiers],
+            rest
+        )
+        .into(),
+        _ => class.into(),
+    }
+}
+
+/// The camel cased identifier used for a custom property, without its dashes (`colorPrimary`)
+pub fn custom_property_identifier(name: &str) -> String {
+    class_identifier(name.trim_start_matches("--"))
+}
+
+/// The pascal cased identifier used for a custom property constructor, without its dashes
+/// (`ColorPrimary`)
+pub fn custom_property_constructor(name: &str) -> String {
+    constructor_identifier(name.trim_start_matches("--"))
+}
+
+/// The pascal cased identifier used for a breakpoint constructor (`Md`, `TwoXl`)
+pub fn constructor_identifier(name: &str) -> String {
+    escape_class_name(name).to_case(Case::Pascal)
+}
+
+/// A float literal that always has a decimal part (`640.0`), as required by Elm, PureScript,
+/// and ReScript
+pub fn float_literal(value: f64) -> String {
+    format!("{:?}", value)
+}
+
+/// A double quoted string literal, valid in all the generated languages
+pub fn string_literal(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// The utility part of a class name, without its variants (`md:`, `hover:`),
+/// important modifier (`!`), nor negative sign (`-`): `md:-mt-2` gives `mt-2`
+pub fn utility_name(class: &str) -> &str {
+    split_variants(class)
+        .1
+        .trim_start_matches('!')
+        .trim_start_matches('-')
+}
+
+/// Splits the variant prefixes (`md:`, `hover:`) of a class name from the rest of it:
+/// `md:hover:-mt-2` gives `(["md", "hover"], "-mt-2")`
+pub fn split_variants(class: &str) -> (Vec<&str>, &str) {
+    let mut variants = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    // Colons inside arbitrary values (`bg-[url(a:b)]`) are not variant separators
+    for (index, c) in class.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ':' if depth == 0 => {
+                variants.push(&class[start..index]);
+
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+
+    (variants, &class[start..])
+}
+
+/// The markdown lines documenting a class in the generated code: its declarations,
+/// the media queries it applies in, and its deprecation. Empty if nothing is known about the class.
+pub fn class_doc_lines(info: &ClassInfo) -> Vec<String> {
+    let mut lines = class_description_lines(info);
+
+    if let Some(deprecation) = &info.deprecated {
+        push_paragraph(
+            &mut lines,
+            if deprecation.note.is_empty() {
+                "**Deprecated**".to_string()
+            } else {
+                format!("**Deprecated**: {}", deprecation)
+            },
+        );
+    }
+
+    lines
+}
+
+/// The lines of a JSDoc comment documenting a class, its deprecation is a `@deprecated` tag
+/// so that editors strike its uses through
+pub fn class_jsdoc_lines(info: &ClassInfo) -> Vec<String> {
+    let mut lines = class_description_lines(info);
+
+    if let Some(deprecation) = &info.deprecated {
+        push_paragraph(&mut lines, format!("@deprecated {}", deprecation));
+    }
+
+    lines
+}
+
+fn push_paragraph(lines: &mut Vec<String>, paragraph: String) {
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    lines.push(paragraph.trim_end().to_string());
+}
+
+fn class_description_lines(info: &ClassInfo) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    if !info.declarations.is_empty() {
+        lines.push("```css".to_string());
+
+        for declaration in info.declarations.iter() {
+            lines.push(format!("{}: {};", declaration.property, declaration.value));
+        }
+
+        lines.push("```".to_string());
+    }
+
+    if !info.media.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        for query in info.media.iter() {
+            lines.push(format!("Applies at `@media {}`", query));
+        }
+    }
+
+    lines
+}
+
+/// The markdown lines documenting an alias in the generated code: its classes
+pub fn alias_doc_lines(members: &[String]) -> Vec<String> {
+    vec![format!("Alias of `{}`", members.join(" "))]
+}
+
+/// The markdown lines documenting a custom property in the generated code:
+/// its default value and its overrides
+pub fn custom_property_doc_lines(custom_property: &CustomProperty) -> Vec<String> {
+    let mut lines = vec![
+        "```css".to_string(),
+        format!("{}: {};", custom_property.name, custom_property.value),
+        "```".to_string(),
+    ];
+
+    if !custom_property.overrides.is_empty() {
+        lines.push(String::new());
+        lines.push("Overridden by:".to_string());
+        lines.push(String::new());
+
+        for value in custom_property.overrides.iter() {
+            let mut selector = format!("`{}`", value.selector);
+
+            for query in value.media.iter() {
+                selector.push_str(&format!(" at `@media {}`", query));
+            }
+
+            lines.push(format!("- {}: `{}`", selector, value.value));
+        }
+    }
+
+    lines
+}
+
+/// Formats the doc lines as a `/** */` comment (TypeScript, ReScript)
+pub fn block_doc_comment(lines: &[String]) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let mut comment = String::from("/**\n");
+
+    for line in lines {
+        if line.is_empty() {
+            comment.push_str(" *\n");
+        } else {
+            comment.push_str(&format!(" * {}\n", line.replace("*/", "*\\/")));
+        }
+    }
+
+    comment.push_str(" */\n");
+
+    comment
+}
+
+/// Indents the lines of a doc comment, for a member of an object, enum, namespace, or module
+pub fn indent_doc_comment(comment: &str) -> String {
+    comment
+        .lines()
+        .map(|line| format!("  {}\n", line))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn it_strips_variants_from_utility_names() {
+        assert_eq!(utility_name("p-2"), "p-2");
+        assert_eq!(utility_name("md:hover:-mt-2"), "mt-2");
+        assert_eq!(utility_name("lg:!p-2"), "p-2");
+        assert_eq!(utility_name("bg-[url(a:b)]"), "bg-[url(a:b)]");
+        assert_eq!(
+            split_variants("md:group-hover:-mt-2"),
+            (vec!["md", "group-hover"], "-mt-2")
+        );
+    }
+
+    #[test]
+    fn it_models_the_prefix_and_important_modifier() {
+        assert_eq!(strip_class_prefix("tw-p-2", "tw-"), "p-2");
+        assert_eq!(strip_class_prefix("md:!-tw-mt-2", "tw-"), "md:!-mt-2");
+        assert_eq!(strip_class_prefix("p-2", "tw-"), "p-2");
+        assert_eq!(strip_class_prefix("tw-p-2", ""), "tw-p-2");
+
+        assert_eq!(class_identifier("!p-2"), "importantP2");
+        assert_eq!(class_identifier("md:!-mt-2"), "mdImportantNegMt2");
+        assert_eq!(class_identifier("!-mt-2"), "importantNegMt2");
+    }
+
+    #[test]
+    fn it_escapes_string_literals() {
+        assert_eq!(string_literal("1rem"), "\"1rem\"");
+        assert_eq!(
+            string_literal("\"Inter\", \\sans"),
+            "\"\\\"Inter\\\", \\\\sans\""
+        );
+    }
+
     #[test]
     fn it_handles_simple_cases() {
         assert_eq!(escape_class_name("foo"), "foo");
@@ -76,6 +363,8 @@ mod tests {
     fn it_converts_some_characters_smartly() {
         assert_eq!(escape_class_name("foo:-bar"), "foo_neg_bar");
         assert_eq!(escape_class_name("foo/bar"), "foo_over_bar");
+        assert_eq!(class_identifier("[mask-type:alpha]"), "maskTypeAlpha");
+        assert_eq!(class_identifier("w-[10px]"), "w10Px");
     }
 
     #[test]
@@ -85,6 +374,28 @@ mod tests {
         assert_eq!(escape_class_name("fOO_bAR"), "foo_bar");
     }
 
+    #[test]
+    fn it_documents_declarations_and_media_queries() {
+        let info = ClassInfo {
+            declarations: vec![crate::Declaration {
+                property: "padding".to_string(),
+                value: "0.5rem".to_string(),
+            }],
+            media: vec!["(min-width: 768px)".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            block_doc_comment(&class_doc_lines(&info)),
+            "/**\n * ```css\n * padding: 0.5rem;\n * ```\n *\n * Applies at `@media (min-width: 768px)`\n */\n"
+        );
+
+        assert_eq!(
+            block_doc_comment(&class_doc_lines(&ClassInfo::default())),
+            ""
+        );
+    }
+
     #[test]
     fn it_escapes_special_characters() {
         assert_eq!(escape_class_name("foo#"), "foo_");
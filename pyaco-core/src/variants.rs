@@ -0,0 +1,203 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::utils::{class_identifier, strip_class_prefix};
+use crate::Classes;
+
+/// The name of the function applying the important modifier (`!`)
+pub const IMPORTANT_FUNCTION: &str = "important";
+
+/// A function of the variant API, prefixing the classes it's applied to with its variant
+#[derive(Debug)]
+pub struct VariantFunction {
+    /// The variant, without the colon (`md`, `group-hover`), or `important`
+    pub variant: String,
+    /// What the function prefixes the classes with (`md:`, or `!` for the important modifier)
+    pub modifier: String,
+    /// The classes the function can be applied to, and the class it then returns (`p-2` gives `md:p-2`)
+    pub classes: BTreeMap<String, String>,
+}
+
+impl VariantFunction {
+    pub fn arguments(&self) -> impl Iterator<Item = &String> {
+        self.classes.keys()
+    }
+}
+
+/// The variant functions (`md(p2)`, `hover(bgRed500)`) generated instead of a constant per
+/// class with variants (`mdP2`, `hoverBgRed500`).
+///
+/// A class is applied through the function of its outermost variant, to the class without this
+/// variant (`md:hover:bg-red-500` is `md(hover(bgRed500))`), which must exist in the stylesheet.
+/// Otherwise, or if the function name conflicts with a class identifier (without the Tailwind
+/// prefix, as declared), it's kept as a constant.
+/// The important modifier is applied by the `important` function (`md:!p-2` is `md(important(p2))`).
+#[derive(Debug, Default)]
+pub struct VariantApi {
+    functions: Vec<VariantFunction>,
+    /// The classes available through the functions
+    covered: HashSet<String>,
+}
+
+impl VariantApi {
+    pub fn new(classes: &Classes, class_prefix: &str) -> Self {
+        let identifier = |class: &str| class_identifier(&strip_class_prefix(class, class_prefix));
+
+        let identifiers = classes
+            .keys()
+            .map(|class| identifier(class))
+            .collect::<HashSet<_>>();
+
+        let mut functions: BTreeMap<(&str, String), BTreeMap<String, String>> = BTreeMap::new();
+
+        for (class, info) in classes {
+            let (variant, modifier) = match info.variants.first() {
+                Some(variant) => (variant.as_str(), format!("{}:", variant)),
+                None if class.starts_with('!') => (IMPORTANT_FUNCTION, "!".to_string()),
+                None => continue,
+            };
+
+            let argument = &class[modifier.len()..];
+
+            if !classes.contains_key(argument) || identifiers.contains(&identifier(variant)) {
+                continue;
+            }
+
+            functions
+                .entry((variant, modifier))
+                .or_default()
+                .insert(argument.to_string(), class.clone());
+        }
+
+        let functions = functions
+            .into_iter()
+            .map(|((variant, modifier), classes)| VariantFunction {
+                variant: variant.to_string(),
+                modifier,
+                classes,
+            })
+            .collect::<Vec<_>>();
+
+        let covered = functions
+            .iter()
+            .flat_map(|function| function.classes.values().cloned())
+            .collect();
+
+        VariantApi { functions, covered }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    pub fn functions(&self) -> &[VariantFunction] {
+        self.functions.as_slice()
+    }
+
+    /// Whether the class is available through a variant function, and not as a constant
+    pub fn is_covered(&self, class: &str) -> bool {
+        self.covered.contains(class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::split_variants;
+    use crate::ClassInfo;
+
+    fn classes(names: &[&str]) -> Classes {
+        names
+            .iter()
+            .map(|name| {
+                let info = ClassInfo {
+                    variants: split_variants(name)
+                        .0
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                    ..Default::default()
+                };
+
+                (name.to_string(), info)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_applies_classes_through_their_outermost_variant() {
+        let classes = classes(&[
+            "p-2",
+            "md:p-2",
+            "bg-red-500",
+            "hover:bg-red-500",
+            "md:hover:bg-red-500",
+            "sm:p-4",
+        ]);
+
+        let api = VariantApi::new(&classes, "");
+
+        let functions = api
+            .functions()
+            .iter()
+            .map(|function| {
+                (
+                    function.variant.as_str(),
+                    function.arguments().map(String::as_str).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            functions,
+            [
+                ("hover", vec!["bg-red-500"]),
+                ("md", vec!["hover:bg-red-500", "p-2"])
+            ]
+        );
+
+        assert!(api.is_covered("md:hover:bg-red-500"));
+        assert!(!api.is_covered("p-2"));
+        // `p-4` doesn't exist
+        assert!(!api.is_covered("sm:p-4"));
+    }
+
+    #[test]
+    fn it_applies_the_important_modifier_through_a_function() {
+        let classes = classes(&["p-2", "!p-2", "md:p-2", "md:!p-2", "!p-4"]);
+
+        let api = VariantApi::new(&classes, "");
+
+        let important = &api.functions()[0];
+
+        assert_eq!(important.variant, "important");
+        assert_eq!(important.modifier, "!");
+        assert_eq!(important.arguments().collect::<Vec<_>>(), ["p-2"]);
+        assert_eq!(api.functions()[1].modifier, "md:");
+
+        assert!(api.is_covered("md:!p-2"));
+        // `p-4` doesn't exist
+        assert!(!api.is_covered("!p-4"));
+    }
+
+    #[test]
+    fn it_keeps_constants_conflicting_with_variant_functions() {
+        let classes = classes(&["dark", "bg-black", "dark:bg-black"]);
+
+        let api = VariantApi::new(&classes, "");
+
+        assert!(api.functions().is_empty());
+        assert!(!api.is_covered("dark:bg-black"));
+    }
+
+    #[test]
+    fn it_compares_the_function_names_with_the_identifiers_without_prefix() {
+        let classes = classes(&["tw-dark", "tw-bg-black", "dark:tw-bg-black"]);
+
+        assert_eq!(VariantApi::new(&classes, "").functions().len(), 1);
+
+        let api = VariantApi::new(&classes, "tw-");
+
+        assert!(api.functions().is_empty());
+        assert!(!api.is_covered("dark:tw-bg-black"));
+    }
+}
@@ -0,0 +1,31 @@
+use pyaco_core::{extract_from_file, RuntimeTemplate, TemplateOptions};
+use std::env::{set_current_dir, temp_dir};
+use std::fs::{create_dir_all, write};
+
+// The source roots are detected from the current directory, which this test changes,
+// so it's kept out of the unit tests running in parallel
+#[test]
+fn it_renders_outside_of_the_elm_source_roots() {
+    let directory = temp_dir().join(format!("pyaco-runtime-template-{}", std::process::id()));
+
+    create_dir_all(&directory).unwrap();
+    set_current_dir(&directory).unwrap();
+
+    write("elm.json", r#"{ "source-directories": ["src"] }"#).unwrap();
+    write("Css.svelte", "{{ module_name }}: {{ classes|length }}").unwrap();
+    write("styles.css", ".p-2 { padding: 0.5rem; }").unwrap();
+
+    let classes = extract_from_file("styles.css").unwrap();
+
+    let options = TemplateOptions::new("gen", "Css");
+
+    let template = RuntimeTemplate::new("Css.svelte", &options, &classes).unwrap();
+
+    assert_eq!(template.render().unwrap(), "gen.Css: 1");
+
+    let options = TemplateOptions::new("src/Styles", "Css");
+
+    let template = RuntimeTemplate::new("Css.svelte", &options, &classes).unwrap();
+
+    assert_eq!(template.render().unwrap(), "Styles.Css: 1");
+}
@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Result};
+use convert_case::{Case, Casing};
+use glob::{MatchOptions, Pattern};
+use pyaco_core::Lang;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{Generator, Target};
+
+/// Wildcards only match within a directory, `**` matches across them
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// The stylesheets matched by a glob input (`./styles/**/*.css`), each generating its own modules
+#[derive(Debug)]
+pub struct Batch {
+    /// The directory the glob starts from (`styles`), the output paths mirror the paths of the
+    /// stylesheets relative to it. Empty for the current directory, as glob leaves out `./`.
+    base: PathBuf,
+    /// The rest of the glob (`**/*.css`), matched against the paths relative to the base
+    pattern: Pattern,
+}
+
+impl Batch {
+    pub fn new(glob: &str) -> Result<Self> {
+        let mut base = PathBuf::new();
+        let mut pattern = PathBuf::new();
+
+        for component in Path::new(glob).components() {
+            let is_wildcard = component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(&['*', '?', '['][..]);
+
+            if is_wildcard || !pattern.as_os_str().is_empty() {
+                pattern.push(component);
+            } else if component != Component::CurDir {
+                base.push(component);
+            }
+        }
+
+        // A plain path is a batch of a single stylesheet, relative to its directory
+        if pattern.as_os_str().is_empty() {
+            pattern = base
+                .file_name()
+                .map(PathBuf::from)
+                .ok_or_else(|| anyhow!("{} doesn't match any stylesheet", glob))?;
+
+            base.pop();
+        }
+
+        let pattern = Pattern::new(&pattern.to_string_lossy())
+            .map_err(|error| anyhow!("{} is not a valid glob: {}", glob, error))?;
+
+        Ok(Batch { base, pattern })
+    }
+
+    /// The directory the glob starts from
+    pub fn directory(&self) -> &Path {
+        if self.base.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            &self.base
+        }
+    }
+
+    /// The path of a stylesheet, from its path relative to the base
+    pub fn path(&self, stylesheet: &Path) -> PathBuf {
+        self.base.join(stylesheet)
+    }
+
+    /// Whether a path, relative to the base, is a stylesheet of the batch
+    pub fn matches(&self, stylesheet: &Path) -> bool {
+        self.pattern.matches_path_with(stylesheet, MATCH_OPTIONS)
+    }
+
+    /// The paths of the matched stylesheets, relative to the base
+    pub fn stylesheets(&self) -> Result<Vec<PathBuf>> {
+        let glob = self.base.join(self.pattern.as_str());
+
+        let mut stylesheets = Vec::new();
+
+        for path in glob::glob_with(&glob.to_string_lossy(), MATCH_OPTIONS)? {
+            let path = path?;
+
+            if path.is_file() {
+                stylesheets.push(path.strip_prefix(&self.base)?.to_path_buf());
+            }
+        }
+
+        Ok(stylesheets)
+    }
+
+    /// The targets of a stylesheet, generated in the same directory relative to the output
+    /// directory of each target as the stylesheet is to the base, and named after it. The Elm
+    /// and PureScript modules are pascal cased, `features/cart.css` gives `Features/Cart.elm`.
+    pub fn targets(&self, targets: &[Target], stylesheet: &Path) -> Vec<Target> {
+        let directory = stylesheet.parent().unwrap_or_else(|| Path::new(""));
+
+        let output_filename = stylesheet
+            .file_stem()
+            .map(|file_stem| file_stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        targets
+            .iter()
+            .map(|target| {
+                let is_module_path = matches!(
+                    target.generator,
+                    Generator::Lang(Lang::Elm) | Generator::Lang(Lang::Purescript)
+                );
+
+                let name = |name: &str| {
+                    if is_module_path {
+                        name.to_case(Case::Pascal)
+                    } else {
+                        name.to_string()
+                    }
+                };
+
+                Target {
+                    generator: target.generator.clone(),
+                    output_directory: directory
+                        .iter()
+                        .fold(PathBuf::from(&target.output_directory), |path, segment| {
+                            path.join(name(&segment.to_string_lossy()))
+                        })
+                        .to_string_lossy()
+                        .into_owned(),
+                    output_filename: name(&output_filename),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_mirrors_the_stylesheet_paths() {
+        let batch = Batch::new("./styles/**/*.css").unwrap();
+
+        assert_eq!(batch.directory(), Path::new("styles"));
+        assert!(batch.matches(Path::new("cart.css")));
+        assert!(batch.matches(Path::new("features/cart.css")));
+        assert!(!batch.matches(Path::new("features/cart.scss")));
+
+        let targets = batch.targets(
+            &[Target {
+                generator: Generator::Lang(Lang::Typescript),
+                output_directory: "generated".to_string(),
+                output_filename: String::new(),
+            }],
+            Path::new("features/cart.css"),
+        );
+
+        assert_eq!(targets[0].output_directory, "generated/features");
+        assert_eq!(targets[0].output_filename, "cart");
+
+        let targets = batch.targets(
+            &[
+                Target {
+                    generator: Generator::Lang(Lang::Elm),
+                    output_directory: "src/Styles".to_string(),
+                    output_filename: String::new(),
+                },
+                Target {
+                    generator: Generator::Lang(Lang::Purescript),
+                    output_directory: "src".to_string(),
+                    output_filename: String::new(),
+                },
+            ],
+            Path::new("features/shopping-cart.css"),
+        );
+
+        assert_eq!(targets[0].output_directory, "src/Styles/Features");
+        assert_eq!(targets[0].output_filename, "ShoppingCart");
+        assert_eq!(targets[1].output_directory, "src/Features");
+        assert_eq!(targets[1].output_filename, "ShoppingCart");
+
+        let batch = Batch::new("styles.css").unwrap();
+
+        assert_eq!(batch.directory(), Path::new("."));
+        assert!(batch.matches(Path::new("styles.css")));
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use pyaco_core::GeneratedFile;
+use similar::TextDiff;
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+
+/// Compares the generated files with the ones on disk, and prints the classes added to and
+/// removed from each stale file
+pub fn check_files(files: &[GeneratedFile]) -> Result<()> {
+    let mut stale_files = 0;
+
+    for file in files {
+        let current_code = match file.stale_content()? {
+            None => {
+                info!("{} is up to date", file.path);
+
+                continue;
+            }
+            Some(current_code) => current_code,
+        };
+
+        stale_files += 1;
+
+        if current_code.is_empty() {
+            println!("{} is missing", file.path);
+
+            continue;
+        }
+
+        let changes = ClassChanges::new(&current_code, &file.code);
+
+        if changes.is_empty() {
+            // The classes are the same, their documentation or the generation options changed
+            let diff = TextDiff::from_lines(current_code.as_str(), file.code.as_str());
+
+            print!(
+                "{}",
+                diff.unified_diff()
+                    .context_radius(1)
+                    .header(file.path.as_str(), file.path.as_str())
+            );
+        } else {
+            println!("{} is out of date:\n{}", file.path, changes);
+        }
+    }
+
+    if stale_files > 0 {
+        return Err(anyhow!(
+            "{} generated file(s) out of date, run pyaco generate without --check to update them",
+            stale_files
+        ));
+    }
+
+    info!("Generated files are all up to date");
+
+    Ok(())
+}
+
+/// The classes added to and removed from a generated file. The classes are generated as string
+/// literals (`"p-2"`), while the other literals come from the templates and don't change, so the
+/// literals only one version of the file contains are the class changes.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ClassChanges<'a> {
+    added: BTreeSet<&'a str>,
+    removed: BTreeSet<&'a str>,
+}
+
+impl<'a> ClassChanges<'a> {
+    fn new(current_code: &'a str, code: &'a str) -> Self {
+        let current_literals = string_literals(current_code);
+        let literals = string_literals(code);
+
+        ClassChanges {
+            added: literals.difference(&current_literals).copied().collect(),
+            removed: current_literals.difference(&literals).copied().collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<'a> Display for ClassChanges<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for class in self.added.iter() {
+            writeln!(f, "  + {}", class)?;
+        }
+
+        for class in self.removed.iter() {
+            writeln!(f, "  - {}", class)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The content of the double quoted string literals of the code, escapes are kept as is.
+/// None of the generated languages allows a line break in a double quoted literal.
+fn string_literals(code: &str) -> BTreeSet<&str> {
+    let mut literals = BTreeSet::new();
+    let mut literal_start = None;
+    let mut is_escaped = false;
+
+    for (index, c) in code.char_indices() {
+        match (literal_start, c) {
+            (Some(_), _) if is_escaped => is_escaped = false,
+            (Some(_), '\\') => is_escaped = true,
+            (Some(start), '"') => {
+                literals.insert(&code[start..index]);
+
+                literal_start = None;
+            }
+            (_, '\n') => literal_start = None,
+            (None, '"') => literal_start = Some(index + 1),
+            _ => (),
+        }
+    }
+
+    literals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, Options};
+    use clap::Clap;
+    use std::fs::{create_dir_all, write};
+
+    #[test]
+    fn it_finds_the_class_changes() {
+        let current_code = r#"export const p2 = buildCssClass("p-2");
+export const join = (classNames) => classNames.join(" ");
+export const w12 = buildCssClass("w-1/2");"#;
+
+        let code = r#"export const join = (classNames) => classNames.join(" ");
+export const pt2 = buildCssClass("pt-2");
+export const w12 = buildCssClass("w-1/2");
+export const quote = buildCssClass("before:content-[\"\"]");"#;
+
+        let changes = ClassChanges::new(current_code, code);
+
+        assert_eq!(
+            changes.added.into_iter().collect::<Vec<_>>(),
+            [r#"before:content-[\"\"]"#, "pt-2"]
+        );
+        assert_eq!(changes.removed.into_iter().collect::<Vec<_>>(), ["p-2"]);
+        assert!(ClassChanges::new(code, code).is_empty());
+    }
+
+    #[test]
+    fn it_fails_without_writing_in_check_mode() {
+        let directory = std::env::temp_dir().join(format!("pyaco-check-{}", std::process::id()));
+
+        create_dir_all(&directory).unwrap();
+
+        let input = directory.join("styles.css");
+        let output_directory = directory.join("generated");
+
+        write(&input, ".p-2 { padding: 0.5rem; }").unwrap();
+
+        let options = Options::try_parse_from([
+            "generate",
+            "-i",
+            &input.to_string_lossy(),
+            "-l",
+            "typescript",
+            "-o",
+            &output_directory.to_string_lossy(),
+            "-f",
+            "css",
+            "--check",
+        ])
+        .unwrap();
+
+        assert!(run(options).is_err());
+        assert!(!output_directory.exists());
+    }
+
+    #[test]
+    fn it_checks_the_manifest_and_templates_like_the_modules() {
+        let directory =
+            std::env::temp_dir().join(format!("pyaco-check-outputs-{}", std::process::id()));
+
+        create_dir_all(&directory).unwrap();
+
+        let input = directory.join("styles.css");
+        let template = directory.join("Css.txt");
+        let manifest = directory.join("classes.json");
+        let output_directory = directory.join("generated");
+
+        write(&input, ".p-2 { padding: 0.5rem; }").unwrap();
+        write(
+            &template,
+            "{% for class in classes %}{{ class }}\n{% endfor %}",
+        )
+        .unwrap();
+
+        let options = |check: bool| {
+            let mut args = vec![
+                "generate".to_string(),
+                "-i".to_string(),
+                input.to_string_lossy().into_owned(),
+                "-t".to_string(),
+                template.to_string_lossy().into_owned(),
+                "-o".to_string(),
+                output_directory.to_string_lossy().into_owned(),
+                "-f".to_string(),
+                "css".to_string(),
+                "--manifest".to_string(),
+                manifest.to_string_lossy().into_owned(),
+            ];
+
+            if check {
+                args.push("--check".to_string());
+            }
+
+            Options::try_parse_from(args).unwrap()
+        };
+
+        assert!(run(options(true)).is_err());
+        assert!(!manifest.exists());
+
+        run(options(false)).unwrap();
+
+        assert!(run(options(true)).is_ok());
+
+        write(
+            &input,
+            ".p-2 { padding: 0.5rem; } .p-3 { padding: 0.75rem; }",
+        )
+        .unwrap();
+
+        assert!(run(options(true)).is_err());
+    }
+
+    #[test]
+    fn it_reports_every_stale_stylesheet_of_a_batch() {
+        let directory =
+            std::env::temp_dir().join(format!("pyaco-check-batch-{}", std::process::id()));
+
+        create_dir_all(directory.join("styles")).unwrap();
+
+        write(directory.join("styles/cart.css"), ".p-2 {}").unwrap();
+        write(directory.join("styles/home.css"), ".m-2 {}").unwrap();
+
+        let output_directory = directory.join("generated");
+
+        let options = Options::try_parse_from([
+            "generate",
+            "-i",
+            &directory.join("styles/*.css").to_string_lossy(),
+            "-l",
+            "typescript",
+            "-o",
+            &output_directory.to_string_lossy(),
+            "--batch",
+            "--check",
+        ])
+        .unwrap();
+
+        let error = run(options).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("2 of 2 stylesheet(s) out of date"));
+        assert!(!output_directory.exists());
+    }
+}
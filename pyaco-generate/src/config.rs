@@ -0,0 +1,74 @@
+use pyaco_core::Config;
+use std::mem::take;
+
+use crate::Options;
+
+/// Fills the options that were not provided with the `[generate]` section of the configuration
+/// file, the `[aliases]` and `[deprecated]` classes it generates, and the `[filter]` selecting them
+pub fn apply_config(config: Config, options: &mut Options) {
+    let filter = config.filter(take(&mut options.include), take(&mut options.exclude));
+
+    options.include = filter.include;
+    options.exclude = filter.exclude;
+
+    if options.typescript_style.is_none() {
+        options.typescript_style = config.generate.typescript_style;
+    }
+
+    if options.class_prefix.is_none() {
+        options.class_prefix = config.generate.class_prefix;
+    }
+
+    if options.aliases.is_empty() {
+        options.aliases = config.aliases;
+    }
+
+    if options.deprecations.is_empty() {
+        options.deprecations = config.deprecations;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Clap;
+    use pyaco_core::TypescriptStyle;
+    use std::fs::write;
+
+    #[test]
+    fn it_fills_the_options_that_were_not_provided() {
+        let path = std::env::temp_dir().join(format!("pyaco-config-{}.toml", std::process::id()));
+
+        write(
+            &path,
+            "[generate]\ntypescript-style = \"const-enum\"\nclass-prefix = \"tw-\"\n\n\
+             [aliases]\nbtn = \"px-4 py-2\"\n\n[filter]\ninclude = [\"p-*\"]\nexclude = [\"p-2\"]\n",
+        )
+        .unwrap();
+
+        let mut options = Options::try_parse_from([
+            "generate",
+            "-i",
+            "styles.css",
+            "-l",
+            "typescript",
+            "--class-prefix",
+            "ui-",
+            "--include",
+            "m-*",
+        ])
+        .unwrap();
+
+        apply_config(Config::read(&path).unwrap(), &mut options);
+
+        assert_eq!(options.typescript_style, Some(TypescriptStyle::ConstEnum));
+        assert_eq!(options.class_prefix(), "ui-");
+        assert_eq!(options.aliases["btn"], ["px-4", "py-2"]);
+
+        let filter = options.class_filter();
+
+        assert!(filter.accepts("m-4"));
+        assert!(!filter.accepts("p-4"));
+        assert!(!filter.accepts("p-2"));
+    }
+}
@@ -1,43 +1,226 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Clap;
-use log::{debug, info, log_enabled, warn, Level};
-use notify::event::{DataChange, ModifyKind};
-use notify::{Event, EventKind, RecursiveMode, Watcher};
+use log::{error, info, log_enabled, warn, Level};
+use notify::RecursiveMode;
 use pyaco_core::{
-    resolve_path, ElmTemplate, InputType, Lang, LangTemplate, PurescriptTemplate, RescriptTemplate,
-    RescriptTypeTemplate, RescriptiTemplate, TypescriptTemplate, TypescriptType1Template,
-    TypescriptType2Template,
+    apply_deprecations, generate_adapter, generate_javascript, generate_split, generate_tokens,
+    is_css_module, resolve_path, split_classes, validate_aliases, validate_class_prefix, Aliases,
+    Chunk, ClassFilter, ClassPattern, Config, CssModule, Deprecations, DesignTokens, ElmTemplate,
+    Framework, GeneratedFile, InputType, JsFormat, JsonSchema, Lang, LangTemplate, Manifest,
+    PurescriptTemplate, RescriptTemplate, RescriptTypeTemplate, RescriptiTemplate, RuntimeTemplate,
+    SplitBy, Stylesheet, TemplateOptions, TypescriptStyle, TypescriptTemplate,
+    TypescriptType1Template, TypescriptType2Template, CONFIG_FILE_NAME,
 };
-use std::fs::create_dir_all;
-use std::path::Path;
-use std::process;
-use std::sync::mpsc::channel;
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, remove_file};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use batch::Batch;
+use check::check_files;
+use config::apply_config;
+use watch::{is_changed, watch_directory, StylesheetState};
+use watch_errors::WatchErrors;
+
+pub use target::{Generator, Target};
+
+mod batch;
+mod check;
+mod config;
+mod target;
+mod watch;
+mod watch_errors;
 
 #[derive(Clap, Debug)]
 pub struct Options {
-    /// CSS file path and/or URL to parse and generate code from
+    /// CSS file path and/or URL to parse and generate code from, or a glob in batch and CSS Modules modes
     #[clap(short, long)]
     pub input: String,
 
+    /// Only generate the classes matching this glob (`p-*`), or regex written between slashes (`/^bg-/`), can be repeated, defaults to the `include` patterns of the `[filter]` section of pyaco.toml
+    #[clap(long = "include", multiple_occurrences = true)]
+    pub include: Vec<ClassPattern>,
+
+    /// Don't generate the classes matching this glob (`float-*`), or regex written between slashes (`/-(amber|lime)-/`), can be repeated, defaults to the `exclude` patterns of the `[filter]` section of pyaco.toml
+    #[clap(long = "exclude", multiple_occurrences = true)]
+    pub exclude: Vec<ClassPattern>,
+
     /// Directory for generated code
     #[clap(short, long, default_value = "./")]
     pub output_directory: String,
 
     /// Filename (without extension) used for the generated code
-    #[clap(short = 'f', long)]
-    pub output_filename: String,
+    #[clap(short = 'f', long, required_unless_present_any = &["targets", "css-modules", "batch"])]
+    pub output_filename: Option<String>,
 
     /// Language used in generated code (elm|purescript|rescript|typescript|typescript-type-1|typescript-type-2)
-    #[clap(short, long)]
-    pub lang: Lang,
+    #[clap(short, long, required_unless_present_any = &["template", "targets"])]
+    pub lang: Option<Lang>,
+
+    /// Jinja-like template used to generate the code instead of a built-in language, the generated file takes its extension
+    #[clap(short, long, conflicts_with = "lang")]
+    pub template: Option<String>,
+
+    /// Additional target formatted as <lang>:<output directory>:<output filename>, can be repeated to generate several languages and files from a single css extraction
+    #[clap(long = "target", multiple_occurrences = true)]
+    pub targets: Vec<Target>,
+
+    /// Directory Elm and PureScript module names are relative to, defaults to the elm.json or spago config source directories if any
+    #[clap(long)]
+    pub source_root: Option<String>,
+
+    /// Also group the identifiers into namespaces by utility category (`Spacing.p2`), TypeScript and ReScript only
+    #[clap(long)]
+    pub namespaces: bool,
+
+    /// Generate functions for the variants (`md(p2)`, `hover(bgRed500)`) instead of a constant per class with variants, TypeScript, ReScript, and Elm only
+    #[clap(long)]
+    pub variants: bool,
+
+    /// Also generate a typed `Breakpoint` enum with the pixel widths of the media queries, named after the variants of their classes (`md`)
+    #[clap(long)]
+    pub breakpoints: bool,
+
+    /// Also generate a typed `AnimationName` listing the `@keyframes` of the stylesheet
+    #[clap(long)]
+    pub animations: bool,
+
+    /// Also generate runtime guards and parsers checking strings (from a CMS, an API, etc...) against the classes: `isCssClass`, `parseClasses`, and a json `decoder` in Elm
+    #[clap(long)]
+    pub guards: bool,
+
+    /// Also generate a module typing the custom properties (`--color-primary`) next to each target, named after the output filename suffixed with `Tokens`
+    #[clap(long)]
+    pub tokens: bool,
+
+    /// Also generate a module of framework helpers (react|vue|svelte|elm-html|halogen) next to each target supporting it, named after the output filename suffixed with the framework (`CssReact`), can be repeated
+    #[clap(long = "adapter", multiple_occurrences = true)]
+    pub adapters: Vec<Framework>,
+
+    /// The Tailwind `prefix` of the classes (`tw-`), left out of the identifiers (`tw-p-2` is `p2`) but kept in the class names, defaults to the `class-prefix` of the `[generate]` section of pyaco.toml
+    #[clap(long)]
+    pub class_prefix: Option<String>,
+
+    /// How the typescript generator declares the classes (constants|object|const-enum|namespace): a constant per class (`p2`), a single frozen object map (`Css.p2`), a `const enum` (not usable with isolatedModules, as required by esbuild, swc, and Vite), or a namespace, defaults to the `typescript-style` of the `[generate]` section of pyaco.toml, or to constants
+    #[clap(long)]
+    pub typescript_style: Option<TypescriptStyle>,
+
+    /// Emit (esm|cjs|both) JavaScript modules (`.mjs`, `.cjs`) with their declaration files (`.d.mts`, `.d.cts`) instead of TypeScript, typescript generators only
+    #[clap(long)]
+    pub javascript: Option<JsFormat>,
+
+    /// Split the generated code into several modules grouping the classes by (prefix|category), re-exported by an aggregator module
+    #[clap(long)]
+    pub split: Option<SplitBy>,
+
+    /// Maximum number of classes per module in split mode, bigger groups are split again
+    #[clap(long, default_value = "1000")]
+    pub max_chunk_classes: usize,
+
+    /// Generate typings next to every CSS Module (`*.module.css`) matching the input glob instead, a `Button.module.css.d.ts` declaration for typescript, or a `ButtonCss.res` binding for rescript, typing the record of the local classes
+    #[clap(long)]
+    pub css_modules: bool,
+
+    /// Generate modules for every stylesheet matching the input glob instead, named after the stylesheet, in the same directory relative to the output directory of each target as the stylesheet is to the base directory of the glob, the output filename is ignored
+    #[clap(long, conflicts_with_all = &["css-modules", "manifest", "json-schema", "tokens-json"])]
+    pub batch: bool,
 
     /// Watch for changes in the provided css file and regenarate the code (doesn't work with URL)
     #[clap(short, long)]
     pub watch: bool,
+
+    /// Don't write anything, exit with an error and print a diff if the generated files are not up to date
+    #[clap(long, conflicts_with = "watch")]
+    pub check: bool,
+
+    /// In watch mode, also write the errors of the generation to this file for dev servers to display them, it's removed once the code is generated successfully again
+    #[clap(long, requires = "watch")]
+    pub error_overlay: Option<String>,
+
+    /// Also write a JSON manifest describing every class (identifier, source, declarations, and media queries) to this path
+    #[clap(long)]
+    pub manifest: Option<String>,
+
+    /// Also write a JSON Schema whose enum lists every class to this path
+    #[clap(long)]
+    pub json_schema: Option<String>,
+
+    /// Also write the custom properties, with their overrides, as JSON design tokens to this path
+    #[clap(long)]
+    pub tokens_json: Option<String>,
+
+    /// Named compositions of classes generated as composite constants, from the `[aliases]` section of pyaco.toml
+    #[clap(skip)]
+    pub aliases: Aliases,
+
+    /// Classes documented as deprecated, on top of the annotated ones, from the `[deprecated]` section of pyaco.toml
+    #[clap(skip)]
+    pub deprecations: Deprecations,
 }
 
-pub fn run(options: Options) -> Result<()> {
-    let input = InputType::from_path(options.input);
+impl Options {
+    /// The Tailwind prefix of the classes, empty if they have none
+    pub fn class_prefix(&self) -> &str {
+        self.class_prefix.as_deref().unwrap_or_default()
+    }
+
+    /// The classes kept from the extraction
+    pub fn class_filter(&self) -> ClassFilter {
+        ClassFilter::new(self.include.clone(), self.exclude.clone())
+    }
+
+    /// All the targets to generate, the one described by the `lang` (or `template`),
+    /// `output_directory`, and `output_filename` options first
+    pub fn all_targets(&self) -> Result<Vec<Target>> {
+        let generator = match (&self.lang, &self.template) {
+            (Some(lang), _) => Some(Generator::Lang(lang.clone())),
+            (None, Some(template)) => Some(Generator::Template(template.clone())),
+            (None, None) => None,
+        };
+
+        let mut targets = Vec::with_capacity(self.targets.len() + 1);
+
+        match (generator, &self.output_filename) {
+            (Some(generator), Some(output_filename)) => targets.push(Target {
+                generator,
+                output_directory: self.output_directory.clone(),
+                output_filename: output_filename.clone(),
+            }),
+            // The targets of a batch are named after each stylesheet
+            (Some(generator), None) if self.batch => targets.push(Target {
+                generator,
+                output_directory: self.output_directory.clone(),
+                output_filename: String::new(),
+            }),
+            (Some(_), None) => return Err(anyhow!("an output filename must be provided")),
+            (None, Some(_)) => return Err(anyhow!("either a lang or a template must be provided")),
+            (None, None) => (),
+        }
+
+        targets.extend(self.targets.iter().cloned());
+
+        if targets.is_empty() {
+            return Err(anyhow!("at least one target must be provided"));
+        }
+
+        Ok(targets)
+    }
+}
+
+pub fn run(mut options: Options) -> Result<()> {
+    apply_config(Config::read(CONFIG_FILE_NAME)?, &mut options);
+
+    if options.css_modules {
+        return run_css_modules(&options);
+    }
+
+    if options.batch {
+        return run_batch(&options);
+    }
+
+    let input = InputType::from_path(&options.input);
+
+    let targets = options.all_targets()?;
 
     if log_enabled!(Level::Info) || log_enabled!(Level::Warn) {
         match input {
@@ -52,127 +235,619 @@ pub fn run(options: Options) -> Result<()> {
         }
     };
 
-    info!("Creating directory {} if needed", options.output_directory);
+    if !options.check {
+        create_output_directories(&options, &targets)?;
+    }
+
+    if let (true, InputType::Path(ref path)) = (options.watch, &input) {
+        return run_watch(path, &options, &targets);
+    }
 
-    create_dir_all(options.output_directory.as_str())?;
+    run_once(&input, &options, &targets)?;
 
-    // Always run at least once, even in watch mode
-    run_once(
-        &input,
-        &options.lang,
-        options.output_directory.as_str(),
-        options.output_filename.as_str(),
-    )?;
+    Ok(())
+}
 
-    if options.watch {
-        if let InputType::Path(ref path) = input {
-            run_watch(
-                path,
-                &options.lang,
-                options.output_directory.as_str(),
-                options.output_filename.as_str(),
-            )?
+fn create_output_directories(options: &Options, targets: &[Target]) -> Result<()> {
+    for target in targets.iter() {
+        info!("Creating directory {} if needed", target.output_directory);
+
+        create_dir_all(target.output_directory.as_str())?;
+
+        // The modules of a split target are generated in a directory named after the output filename
+        if options.split.is_some() && matches!(target.generator, Generator::Lang(_)) {
+            create_dir_all(Path::new(&target.output_directory).join(&target.output_filename))?;
         }
     }
 
     Ok(())
 }
 
-fn run_once(
-    input: &InputType,
-    lang: &Lang,
-    output_directory: &str,
-    output_filename: &str,
+/// Extracts the classes once and generates the code for all the targets, returning the paths
+/// of the generated files, in check mode the generated code is compared with the files on disk instead
+fn run_once(input: &InputType, options: &Options, targets: &[Target]) -> Result<Vec<String>> {
+    let mut stylesheet = input.extract_filtered_stylesheet(&options.class_filter())?;
+
+    apply_deprecations(&mut stylesheet.classes, &options.deprecations);
+
+    let classes = &stylesheet.classes;
+
+    validate_aliases(&options.aliases, |class| classes.contains_key(class))?;
+
+    validate_class_prefix(classes, options.class_prefix())?;
+
+    let mut files = Vec::new();
+
+    if let Some(ref manifest) = options.manifest {
+        let source = input.to_string();
+
+        files.push(Manifest::new(&source, classes, options.class_prefix()).generate(manifest)?);
+    }
+
+    if let Some(ref json_schema) = options.json_schema {
+        files.push(JsonSchema::new(classes).generate(json_schema)?);
+    }
+
+    if let Some(ref tokens_json) = options.tokens_json {
+        let source = input.to_string();
+
+        files
+            .push(DesignTokens::new(&source, &stylesheet.custom_properties).generate(tokens_json)?);
+    }
+
+    if options.tokens && stylesheet.custom_properties.is_empty() {
+        warn!("No custom properties found, the tokens modules are not generated");
+    }
+
+    if options.animations && stylesheet.animations.is_empty() {
+        warn!("No @keyframes found, no AnimationName is generated");
+    }
+
+    match options.typescript_style {
+        Some(TypescriptStyle::Constants) | None => (),
+        Some(_) if options.split.is_some() || options.javascript.is_some() => warn!(
+            "The typescript style only applies to the TypeScript code generated in a single file, the classes are declared as constants"
+        ),
+        Some(TypescriptStyle::ConstEnum) if options.variants => {
+            warn!("Enum members can't be computed, the const-enum style has no variant functions")
+        }
+        Some(_) => (),
+    }
+
+    if options.split.is_some() && !options.aliases.is_empty() {
+        warn!("Aliases are not generated in split mode");
+    }
+
+    for framework in options.adapters.iter() {
+        let is_supported = targets.iter().any(|target| match target.generator {
+            Generator::Lang(ref lang) => framework.supports(lang),
+            Generator::Template(_) => false,
+        });
+
+        if !is_supported {
+            warn!(
+                "None of the targets support {:?} helpers, they are not generated",
+                framework
+            );
+        }
+    }
+
+    let chunks = options.split.map(|split_by| {
+        split_classes(
+            classes,
+            split_by,
+            options.max_chunk_classes,
+            options.class_prefix(),
+        )
+    });
+
+    for target in targets {
+        files.extend(generate_target(
+            target,
+            options,
+            &stylesheet,
+            chunks.as_deref(),
+        )?);
+
+        if options.tokens && !stylesheet.custom_properties.is_empty() {
+            files.extend(generate_target_tokens(target, options, &stylesheet)?);
+        }
+
+        files.extend(generate_target_adapters(target, options, &stylesheet)?);
+    }
+
+    let paths = files.iter().map(|file| file.path.clone()).collect();
+
+    if options.check {
+        check_files(&files)?;
+    } else {
+        for file in files {
+            file.write()?;
+        }
+    }
+
+    Ok(paths)
+}
+
+/// The paths of the files generated from each stylesheet of a batch
+type BatchOutputs = BTreeMap<PathBuf, Vec<String>>;
+
+/// Generates the modules of every stylesheet matching the input glob
+fn run_batch(options: &Options) -> Result<()> {
+    let batch = Batch::new(&options.input)?;
+
+    let targets = options.all_targets()?;
+
+    let stylesheets = batch.stylesheets()?;
+
+    if stylesheets.is_empty() && !options.watch {
+        return Err(anyhow!("no stylesheet matches {}", options.input));
+    }
+
+    if !options.watch {
+        // Every stylesheet is generated (or checked), so that all the failing ones are reported
+        let mut failures = 0;
+
+        for stylesheet in &stylesheets {
+            if let Err(error) = run_batch_stylesheet(&batch, stylesheet, options, &targets) {
+                error!("{}: {}", stylesheet.display(), error);
+
+                failures += 1;
+            }
+        }
+
+        return match failures {
+            0 => Ok(()),
+            _ if options.check => Err(anyhow!(
+                "{} of {} stylesheet(s) out of date or failing, run pyaco generate without --check to update them",
+                failures,
+                stylesheets.len()
+            )),
+            _ => Err(anyhow!(
+                "{} of {} stylesheet(s) failed to generate",
+                failures,
+                stylesheets.len()
+            )),
+        };
+    }
+
+    let mut outputs = BatchOutputs::new();
+
+    let mut errors = WatchErrors::new(options.error_overlay.as_deref());
+
+    for stylesheet in stylesheets {
+        match run_batch_stylesheet(&batch, &stylesheet, options, &targets) {
+            Ok(paths) => {
+                outputs.insert(stylesheet, paths);
+            }
+            Err(error) => errors.report(&stylesheet.to_string_lossy(), &error),
+        }
+    }
+
+    run_batch_watch(&batch, options, &targets, outputs, errors)
+}
+
+/// Generates the modules of a stylesheet of the batch, returning the paths of the generated files
+fn run_batch_stylesheet(
+    batch: &Batch,
+    stylesheet: &Path,
+    options: &Options,
+    targets: &[Target],
+) -> Result<Vec<String>> {
+    let path = batch.path(stylesheet);
+
+    info!("Extracting from file {:?}", path);
+
+    let targets = batch.targets(targets, stylesheet);
+
+    if !options.check {
+        create_output_directories(options, &targets)?;
+    }
+
+    run_once(&InputType::Path(path), options, &targets)
+}
+
+/// Watches the directory the glob starts from: the modules of the stylesheets that appear or
+/// change are generated, the ones of the stylesheets that disappear are removed. A stylesheet that
+/// fails to generate is reported, and keeps its previously generated modules.
+fn run_batch_watch(
+    batch: &Batch,
+    options: &Options,
+    targets: &[Target],
+    mut outputs: BatchOutputs,
+    mut errors: WatchErrors,
 ) -> Result<()> {
-    let classes = input.extract_classes()?;
+    let base = batch.directory().canonicalize()?;
+
+    watch_directory(&base, RecursiveMode::Recursive, |paths| {
+        let stylesheets = paths
+            .iter()
+            .filter_map(|path| path.strip_prefix(&base).ok())
+            .filter(|stylesheet| batch.matches(stylesheet));
+
+        for stylesheet in stylesheets {
+            let source = stylesheet.to_string_lossy();
+
+            match StylesheetState::of(&batch.path(stylesheet)) {
+                StylesheetState::Missing => {
+                    remove_outputs(stylesheet, &mut outputs);
+
+                    errors.forget(&source);
+
+                    continue;
+                }
+                StylesheetState::Empty => continue,
+                StylesheetState::Ready => (),
+            }
+
+            match run_batch_stylesheet(batch, stylesheet, options, targets) {
+                Ok(paths) => {
+                    let previous_paths = outputs.insert(stylesheet.to_path_buf(), paths.clone());
+
+                    // Some modules may not be generated anymore (no custom properties left, etc...)
+                    for path in previous_paths.unwrap_or_default() {
+                        if !paths.contains(&path) {
+                            remove_output(&path);
+                        }
+                    }
+
+                    errors.recover(&source);
+                }
+                Err(error) => errors.report(&source, &error),
+            }
+        }
+    })
+}
+
+/// Removes the files generated from a stylesheet that disappeared
+fn remove_outputs(stylesheet: &Path, outputs: &mut BatchOutputs) {
+    if let Some(paths) = outputs.remove(stylesheet) {
+        info!("Stylesheet {:?} was removed", stylesheet);
+
+        for path in paths {
+            remove_output(&path);
+        }
+    }
+}
+
+fn remove_output(path: &str) {
+    info!("Removing {}", path);
+
+    match remove_file(path) {
+        Err(error) if error.kind() != ErrorKind::NotFound => {
+            warn!("Couldn't remove {}: {}", path, error)
+        }
+        _ => (),
+    }
+}
+
+/// Generates the typings of every CSS Module matching the input glob, next to each of them
+fn run_css_modules(options: &Options) -> Result<()> {
+    let lang = options
+        .lang
+        .as_ref()
+        .ok_or_else(|| anyhow!("a lang must be provided to generate the typings of CSS Modules"))?;
+
+    if options.watch {
+        warn!("Watch mode is not available for CSS Modules, the typings are generated once");
+    }
+
+    let paths = glob::glob(&options.input)?.collect::<Result<Vec<_>, _>>()?;
+
+    let mut files = Vec::new();
+
+    for path in paths.into_iter().filter(|path| is_css_module(path)) {
+        info!("Extracting from CSS Module {:?}", path);
+
+        let module = CssModule::extract(path)?;
+
+        if module.classes.is_empty() {
+            warn!(
+                "{:?} has no local classes, no typings are generated",
+                module.path
+            );
+
+            continue;
+        }
+
+        files.push(module.generate(lang)?);
+    }
+
+    if files.is_empty() {
+        return Err(anyhow!("no CSS Module matches {}", options.input));
+    }
+
+    if options.check {
+        return check_files(&files);
+    }
+
+    for file in files {
+        file.write()?;
+    }
+
+    Ok(())
+}
+
+fn template_options(
+    target: &Target,
+    options: &Options,
+    stylesheet: &Stylesheet,
+) -> TemplateOptions {
+    let animations = if options.animations {
+        stylesheet.animations.keys().cloned().collect()
+    } else {
+        Vec::new()
+    };
+
+    TemplateOptions {
+        source_root: options.source_root.as_ref().map(PathBuf::from),
+        namespaces: options.namespaces,
+        variants: options.variants,
+        breakpoints: options.breakpoints,
+        animations,
+        guards: options.guards,
+        typescript_style: options.typescript_style.unwrap_or_default(),
+        aliases: options.aliases.clone(),
+        class_prefix: options.class_prefix().to_string(),
+        ..TemplateOptions::new(&target.output_directory, &target.output_filename)
+    }
+}
+
+/// Generates the tokens module of a target, custom templates have none
+fn generate_target_tokens(
+    target: &Target,
+    options: &Options,
+    stylesheet: &Stylesheet,
+) -> Result<Vec<GeneratedFile>> {
+    match target.generator {
+        Generator::Lang(ref lang) => generate_tokens(
+            lang,
+            &template_options(target, options, stylesheet),
+            &stylesheet.custom_properties,
+        ),
+        Generator::Template(ref template) => {
+            warn!(
+                "Custom templates have no tokens module, skipping {}",
+                template
+            );
+
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Generates the framework helpers of a target, for the frameworks its lang supports
+fn generate_target_adapters(
+    target: &Target,
+    options: &Options,
+    stylesheet: &Stylesheet,
+) -> Result<Vec<GeneratedFile>> {
+    let mut files = Vec::new();
+
+    let lang = match target.generator {
+        Generator::Lang(ref lang) => lang,
+        Generator::Template(ref template) => {
+            if !options.adapters.is_empty() {
+                warn!(
+                    "Custom templates have no framework helpers, skipping {}",
+                    template
+                );
+            }
+
+            return Ok(files);
+        }
+    };
+
+    for framework in options.adapters.iter() {
+        if framework.supports(lang) {
+            files.extend(generate_adapter(
+                lang,
+                &template_options(target, options, stylesheet),
+                *framework,
+            )?);
+        } else {
+            info!(
+                "No {:?} helpers for {:?}, skipping {}",
+                framework, lang, target.output_filename
+            );
+        }
+    }
+
+    Ok(files)
+}
+
+fn generate_target(
+    target: &Target,
+    options: &Options,
+    stylesheet: &Stylesheet,
+    chunks: Option<&[Chunk]>,
+) -> Result<Vec<GeneratedFile>> {
+    let output_directory = target.output_directory.as_str();
+
+    let output_filename = target.output_filename.as_str();
+
+    let classes = &stylesheet.classes;
+
+    let template_options = template_options(target, options, stylesheet);
+
+    let mut files = Vec::new();
+
+    let lang = match target.generator {
+        Generator::Lang(ref lang) => lang,
+        Generator::Template(ref template) => {
+            if chunks.is_some() {
+                warn!(
+                    "Custom templates can't be split, {} is generated in a single file",
+                    template
+                );
+            }
+
+            let template = RuntimeTemplate::new(template, &template_options, classes)?;
+
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                template.extension(),
+            )?)?);
+
+            return Ok(files);
+        }
+    };
+
+    if let Some(chunks) = chunks {
+        if options.javascript.is_some() {
+            warn!("JavaScript is not emitted in split mode, generating TypeScript");
+        }
+
+        return generate_split(lang, &template_options, chunks);
+    }
+
+    if let Some(format) = options.javascript {
+        match lang {
+            Lang::Typescript | Lang::TypescriptType1 | Lang::TypescriptType2 => {
+                return generate_javascript(lang, &template_options, classes, format);
+            }
+            _ => warn!(
+                "JavaScript can only be emitted by the typescript generators, ignored for {}",
+                output_filename
+            ),
+        }
+    }
 
     match lang {
         Lang::Elm => {
-            let template = ElmTemplate::new(output_directory, output_filename, &classes)?;
+            let template = ElmTemplate::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "elm")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "elm",
+            )?)?);
         }
         Lang::Purescript => {
-            let template = PurescriptTemplate::new(output_directory, output_filename, &classes)?;
+            let template = PurescriptTemplate::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "purs")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "purs",
+            )?)?);
         }
         Lang::Rescript => {
-            let template = RescriptTemplate::new(output_directory, output_filename, &classes)?;
+            let template = RescriptTemplate::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "res")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "res",
+            )?)?);
 
-            let template = RescriptiTemplate::new(output_directory, output_filename, &classes)?;
+            let template = RescriptiTemplate::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "resi")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "resi",
+            )?)?);
         }
         Lang::RescriptType => {
-            let template = RescriptTypeTemplate::new(output_directory, output_filename, &classes)?;
+            let template = RescriptTypeTemplate::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "res")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "res",
+            )?)?);
         }
         Lang::Typescript => {
-            let template = TypescriptTemplate::new(output_directory, output_filename, &classes)?;
+            let template = TypescriptTemplate::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "ts")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "ts",
+            )?)?);
         }
         Lang::TypescriptType1 => {
-            let template =
-                TypescriptType1Template::new(output_directory, output_filename, &classes)?;
+            let template = TypescriptType1Template::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "ts")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "ts",
+            )?)?);
         }
         Lang::TypescriptType2 => {
-            let template =
-                TypescriptType2Template::new(output_directory, output_filename, &classes)?;
+            let template = TypescriptType2Template::new(&template_options, classes)?;
 
-            template.write_to_file(resolve_path(output_directory, output_filename, "ts")?)?;
+            files.push(template.generate(resolve_path(
+                output_directory,
+                output_filename,
+                "ts",
+            )?)?);
         }
     }
 
-    Ok(())
+    Ok(files)
 }
 
-fn run_watch(
-    path: &Path,
-    lang: &Lang,
-    output_directory: &str,
-    output_filename: &str,
-) -> Result<()> {
-    let (tx, rx) = channel();
+/// Watches the directory of the css file rather than the file itself: editors and Tailwind save
+/// files atomically, writing a temporary file then renaming it over the original one, which
+/// replaces the watched file. The file may also be missing for a while, the code is generated
+/// again once it's back.
+fn run_watch(path: &Path, options: &Options, targets: &[Target]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("{:?} is not a file", path))?;
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+    .canonicalize()?;
+
+    let source = path.to_string_lossy();
 
-    let mut watcher = notify::recommended_watcher(move |result| {
-        if tx.send(result).is_err() {
-            debug!("Couldn't send event message to watcher")
+    let mut errors = WatchErrors::new(options.error_overlay.as_deref());
+
+    let mut generate = || match run_once(&InputType::Path(path.to_owned()), options, targets) {
+        Ok(_) => errors.recover(&source),
+        Err(error) => errors.report(&source, &error),
+    };
+
+    // Always run at least once
+    generate();
+
+    let mut is_missing = false;
+
+    watch_directory(&directory, RecursiveMode::NonRecursive, |paths| {
+        if !is_changed(&paths, file_name) {
+            return;
         }
-    })?;
 
-    watcher.watch(path, RecursiveMode::NonRecursive)?;
+        match StylesheetState::of(path) {
+            StylesheetState::Missing => {
+                if !is_missing {
+                    warn!(
+                        "File {:?} was removed, waiting for it to be created again",
+                        path
+                    );
 
-    for result in rx {
-        match result {
-            Ok(Event {
-                kind: EventKind::Modify(ModifyKind::Data(DataChange::Content)),
-                ..
-            }) => run_once(
-                &InputType::Path(path.to_owned()),
-                lang,
-                output_directory,
-                output_filename,
-            )?,
-            Ok(Event {
-                kind: EventKind::Modify(ModifyKind::Name(notify::event::RenameMode::From)),
-                ..
-            }) => {
-                warn!("File {:?} was removed, exiting", path);
-                process::exit(2)
+                    is_missing = true;
+                }
+
+                return;
             }
-            Ok(Event {
-                kind: event_kind, ..
-            }) => debug!("Unhandled event kind: {:?}", event_kind),
-            Err(error) => warn!("Watch error: {}", error),
+            StylesheetState::Empty => return,
+            StylesheetState::Ready => (),
         }
-    }
 
-    Ok(())
+        if is_missing {
+            info!("File {:?} was created again", path);
+
+            is_missing = false;
+        }
+
+        generate();
+    })
 }
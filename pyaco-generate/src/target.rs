@@ -0,0 +1,82 @@
+use pyaco_core::Lang;
+use std::str::FromStr;
+
+/// What generates the code of a target
+#[derive(Clone, Debug)]
+pub enum Generator {
+    Lang(Lang),
+    /// Path to a Jinja-like template rendered at runtime
+    Template(String),
+}
+
+/// A generated output, all the targets of a run share the same extracted classes
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub generator: Generator,
+    pub output_directory: String,
+    pub output_filename: String,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    /// Parses a `<lang>:<output directory>:<output filename>` target
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_target = || {
+            format!(
+                "\"{}\" is not a valid target, should be formatted as <lang>:<output directory>:<output filename>",
+                s
+            )
+        };
+
+        let (lang, rest) = s.split_once(':').ok_or_else(invalid_target)?;
+
+        // The directory is the only part that may contain a `:` (Windows paths)
+        let (output_directory, output_filename) =
+            rest.rsplit_once(':').ok_or_else(invalid_target)?;
+
+        if output_filename.is_empty() {
+            return Err(invalid_target());
+        }
+
+        Ok(Target {
+            generator: Generator::Lang(lang.parse()?),
+            output_directory: if output_directory.is_empty() {
+                "./".to_string()
+            } else {
+                output_directory.to_string()
+            },
+            output_filename: output_filename.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_targets() {
+        let target = "elm:src/Styles:Tailwind".parse::<Target>().unwrap();
+
+        assert!(matches!(target.generator, Generator::Lang(Lang::Elm)));
+        assert_eq!(target.output_directory, "src/Styles");
+        assert_eq!(target.output_filename, "Tailwind");
+
+        let target = "typescript:C:\\styles:css".parse::<Target>().unwrap();
+
+        assert_eq!(target.output_directory, "C:\\styles");
+
+        let target = "typescript::css".parse::<Target>().unwrap();
+
+        assert_eq!(target.output_directory, "./");
+    }
+
+    #[test]
+    fn it_rejects_invalid_targets() {
+        assert!("elm".parse::<Target>().is_err());
+        assert!("elm:Css".parse::<Target>().is_err());
+        assert!("elm:src:".parse::<Target>().is_err());
+        assert!("cobol:src:Css".parse::<Target>().is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use log::{debug, warn};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// How long the watcher waits for a burst of events to be over before generating the code again
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// What a watched stylesheet looks like once its changes are over
+#[derive(Debug, PartialEq, Eq)]
+pub enum StylesheetState {
+    /// Removed, or replaced by something else than a file
+    Missing,
+    /// Editors may create an empty file before writing its content, which is waited for
+    Empty,
+    Ready,
+}
+
+impl StylesheetState {
+    pub fn of(path: &Path) -> Self {
+        match path.metadata() {
+            Ok(metadata) if metadata.is_file() && metadata.len() == 0 => StylesheetState::Empty,
+            Ok(metadata) if metadata.is_file() => StylesheetState::Ready,
+            _ => StylesheetState::Missing,
+        }
+    }
+}
+
+/// Watches a directory, calling `on_change` with the paths changed by each burst of events once
+/// it's over, so that a save touching several files (or the same one several times) is handled once
+pub fn watch_directory<F>(
+    directory: &Path,
+    recursive_mode: RecursiveMode,
+    mut on_change: F,
+) -> Result<()>
+where
+    F: FnMut(BTreeSet<PathBuf>),
+{
+    let (tx, rx) = channel();
+
+    let mut watcher = notify::recommended_watcher(move |result| {
+        if tx.send(result).is_err() {
+            debug!("Couldn't send event message to watcher")
+        }
+    })?;
+
+    watcher.watch(directory, recursive_mode)?;
+
+    while let Some(paths) = next_burst(&rx, WATCH_DEBOUNCE) {
+        if !paths.is_empty() {
+            on_change(paths);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether one of the changed paths is the watched file, by its name in the watched directory
+pub fn is_changed(paths: &BTreeSet<PathBuf>, file_name: &OsStr) -> bool {
+    paths
+        .iter()
+        .any(|changed| changed.file_name() == Some(file_name))
+}
+
+/// Blocks until the first event of a burst, and collects the paths changed until no event is
+/// received for the debounce duration. `None` once the watcher is gone.
+fn next_burst(
+    rx: &Receiver<notify::Result<Event>>,
+    debounce: Duration,
+) -> Option<BTreeSet<PathBuf>> {
+    let mut paths = BTreeSet::new();
+
+    collect_changed_paths(rx.recv().ok()?, &mut paths);
+
+    loop {
+        match rx.recv_timeout(debounce) {
+            Ok(result) => collect_changed_paths(result, &mut paths),
+            Err(RecvTimeoutError::Timeout) => return Some(paths),
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+fn collect_changed_paths(result: notify::Result<Event>, paths: &mut BTreeSet<PathBuf>) {
+    match result {
+        // Reading the files, generating the code included, is not a change
+        Ok(Event {
+            kind: event_kind @ (EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_))),
+            ..
+        }) => debug!("Unhandled event kind: {:?}", event_kind),
+        Ok(event) => paths.extend(event.paths),
+        Err(error) => warn!("Watch error: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, MetadataKind};
+    use std::fs::{create_dir_all, remove_file, write};
+    use std::sync::mpsc::Sender;
+    use std::thread;
+
+    fn send(tx: &Sender<notify::Result<Event>>, kind: EventKind, path: &str) {
+        tx.send(Ok(Event::new(kind).add_path(PathBuf::from(path))))
+            .unwrap();
+    }
+
+    #[test]
+    fn it_collects_the_changes_of_a_burst() {
+        let (tx, rx) = channel();
+
+        send(&tx, EventKind::Create(CreateKind::File), "styles.css");
+        send(
+            &tx,
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            "styles.css",
+        );
+        send(&tx, EventKind::Access(AccessKind::Any), "generated.ts");
+        send(
+            &tx,
+            EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any)),
+            "other.css",
+        );
+        send(
+            &tx,
+            EventKind::Remove(notify::event::RemoveKind::File),
+            "old.css",
+        );
+
+        let paths = next_burst(&rx, Duration::from_millis(10)).unwrap();
+
+        assert_eq!(
+            paths.iter().collect::<Vec<_>>(),
+            [Path::new("old.css"), Path::new("styles.css")]
+        );
+        assert!(is_changed(&paths, OsStr::new("styles.css")));
+        assert!(!is_changed(&paths, OsStr::new("generated.ts")));
+
+        // A burst of reads only is not a change
+        send(&tx, EventKind::Access(AccessKind::Any), "styles.css");
+
+        assert!(next_burst(&rx, Duration::from_millis(10))
+            .unwrap()
+            .is_empty());
+
+        // The events sent until the burst is over are part of it
+        let sender = {
+            let tx = tx.clone();
+
+            thread::spawn(move || {
+                send(&tx, EventKind::Create(CreateKind::File), "a.css");
+                thread::sleep(Duration::from_millis(20));
+                send(&tx, EventKind::Create(CreateKind::File), "b.css");
+            })
+        };
+
+        assert_eq!(
+            next_burst(&rx, Duration::from_millis(500)).unwrap().len(),
+            2
+        );
+
+        sender.join().unwrap();
+
+        drop(tx);
+
+        // The watcher is gone
+        assert!(next_burst(&rx, Duration::from_millis(10)).is_none());
+    }
+
+    #[test]
+    fn it_waits_for_the_content_of_empty_stylesheets() {
+        let directory = std::env::temp_dir().join(format!("pyaco-watch-{}", std::process::id()));
+
+        create_dir_all(&directory).unwrap();
+
+        let path = directory.join("styles.css");
+
+        let _ = remove_file(&path);
+
+        assert_eq!(StylesheetState::of(&path), StylesheetState::Missing);
+        assert_eq!(StylesheetState::of(&directory), StylesheetState::Missing);
+
+        write(&path, "").unwrap();
+
+        assert_eq!(StylesheetState::of(&path), StylesheetState::Empty);
+
+        write(&path, ".p-2 { padding: 0.5rem; }").unwrap();
+
+        assert_eq!(StylesheetState::of(&path), StylesheetState::Ready);
+    }
+}
@@ -0,0 +1,178 @@
+use log::{error, info, warn};
+use std::collections::BTreeMap;
+use std::fs::{remove_file, write};
+use std::io::ErrorKind;
+
+/// The errors of the generations in watch mode, by source: the watcher keeps running, and the
+/// previously generated code is kept until the next successful generation
+pub struct WatchErrors<'a> {
+    /// File the errors are written to for dev servers to display them, removed once they're all fixed
+    overlay: Option<&'a str>,
+    errors: BTreeMap<String, String>,
+}
+
+impl<'a> WatchErrors<'a> {
+    pub fn new(overlay: Option<&'a str>) -> Self {
+        let errors = WatchErrors {
+            overlay,
+            errors: BTreeMap::new(),
+        };
+
+        // The overlay left by a previous run is outdated
+        errors.write_overlay();
+
+        errors
+    }
+
+    /// Reports the failed generation of the code from the source
+    pub fn report(&mut self, source: &str, error: &anyhow::Error) {
+        error!("Couldn't generate the code from {}: {:#}", source, error);
+        warn!(
+            "The previously generated code is kept, waiting for {} to change",
+            source
+        );
+
+        self.errors
+            .insert(source.to_string(), format!("{:#}", error));
+
+        self.write_overlay();
+    }
+
+    /// Announces the successful generation of the code from a source that previously failed
+    pub fn recover(&mut self, source: &str) {
+        if self.errors.remove(source).is_some() {
+            info!("Recovered: the code was generated from {}", source);
+
+            self.write_overlay();
+        }
+    }
+
+    /// Drops the error of a source that disappeared
+    pub fn forget(&mut self, source: &str) {
+        if self.errors.remove(source).is_some() {
+            self.write_overlay();
+        }
+    }
+
+    fn write_overlay(&self) {
+        let overlay = match self.overlay {
+            Some(overlay) => overlay,
+            None => return,
+        };
+
+        let result = if self.errors.is_empty() {
+            match remove_file(overlay) {
+                Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+                result => result,
+            }
+        } else {
+            write(
+                overlay,
+                self.errors
+                    .iter()
+                    .map(|(source, error)| format!("{}: {}\n", source, error))
+                    .collect::<String>(),
+            )
+        };
+
+        if let Err(error) = result {
+            warn!("Couldn't update the error overlay {}: {}", overlay, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use clap::Clap;
+    use std::fs::{create_dir_all, read_to_string};
+    use std::path::Path;
+
+    use crate::{run, Options};
+
+    #[test]
+    fn it_keeps_the_errors_until_recovered_or_forgotten() {
+        let mut errors = WatchErrors::new(None);
+
+        errors.report("a.css", &anyhow!("invalid css"));
+        errors.report("b.css", &anyhow!("no css classes found"));
+
+        assert_eq!(errors.errors.keys().collect::<Vec<_>>(), ["a.css", "b.css"]);
+
+        errors.recover("a.css");
+        errors.forget("b.css");
+
+        assert!(errors.errors.is_empty());
+    }
+
+    #[test]
+    fn it_writes_the_overlay_while_there_are_errors() {
+        let overlay = std::env::temp_dir()
+            .join(format!("pyaco-overlay-{}.txt", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+
+        // The overlay left by a previous run is removed
+        write(&overlay, "styles.css: invalid css\n").unwrap();
+
+        let mut errors = WatchErrors::new(Some(&overlay));
+
+        assert!(!Path::new(&overlay).exists());
+
+        errors.report("a.css", &anyhow!("invalid css"));
+        errors.report("b.css", &anyhow!("no css classes found"));
+
+        assert_eq!(
+            read_to_string(&overlay).unwrap(),
+            "a.css: invalid css\nb.css: no css classes found\n"
+        );
+
+        errors.recover("a.css");
+
+        assert_eq!(
+            read_to_string(&overlay).unwrap(),
+            "b.css: no css classes found\n"
+        );
+
+        errors.forget("b.css");
+
+        assert!(!Path::new(&overlay).exists());
+    }
+
+    #[test]
+    fn it_leaves_the_overlay_alone_outside_watch_mode() {
+        let directory =
+            std::env::temp_dir().join(format!("pyaco-batch-overlay-{}", std::process::id()));
+
+        create_dir_all(&directory).unwrap();
+
+        write(directory.join("styles.css"), ".p-2 { padding: 0.5rem; }").unwrap();
+
+        let overlay = directory.join("overlay.txt");
+
+        write(&overlay, "styles.css: invalid css\n").unwrap();
+
+        let mut options = Options::try_parse_from([
+            "generate",
+            "-i",
+            &directory.join("*.css").to_string_lossy(),
+            "-l",
+            "typescript",
+            "-o",
+            &directory.join("generated").to_string_lossy(),
+            "-f",
+            "css",
+            "--batch",
+        ])
+        .unwrap();
+
+        // Only settable along with watch mode on the command line, but not from Node
+        options.error_overlay = Some(overlay.to_string_lossy().into_owned());
+
+        run(options).unwrap();
+
+        assert!(directory.join("generated/styles.ts").exists());
+        assert!(overlay.exists());
+    }
+}
@@ -4,40 +4,53 @@ extern crate lazy_static;
 use anyhow::{anyhow, Result};
 use proc_macro::TokenStream;
 use proc_macro_error::{abort_call_site, emit_call_site_warning, proc_macro_error};
-use pyaco_core::{extract_classes_from_file, extract_classes_from_url};
+use pyaco_core::{
+    deprecated_classes, extract_from_file, extract_from_url, validate_aliases, Aliases, Classes,
+    Config, Deprecations, InputConfig, CONFIG_FILE_NAME,
+};
 use quote::quote;
-use serde::Deserialize;
-use std::{collections::HashSet, env, fs::File, io::Read, path::PathBuf};
+use std::{collections::HashSet, env, path::PathBuf};
 use syn::{parse_macro_input, LitStr};
 
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
-enum InputConfig {
-    Simple(String),
-    Path { path: String },
-    Url { url: String },
-}
+lazy_static! {
+    static ref CONFIG: Config = read_config().expect("couldn't read config file");
+    static ref CLASSES: Classes = {
+        let input = &CONFIG
+            .general
+            .as_ref()
+            .expect("the [general] section of pyaco.toml must provide the css input")
+            .input;
+
+        let mut classes = match input {
+            InputConfig::Simple(path) | InputConfig::Path { path } => {
+                extract_from_file(path)
+            }
+            InputConfig::Url { url } => extract_from_url(url),
+        }
+        .expect("css could not be loaded");
 
-#[derive(Debug, Deserialize)]
-struct GeneralConfig {
-    input: InputConfig,
-}
+        // Filtered out classes are rejected like unknown ones
+        CONFIG.filter.apply(&mut classes).expect("invalid filter");
 
-#[derive(Debug, Deserialize)]
-struct Config {
-    general: GeneralConfig,
-}
+        classes
+    };
+    static ref ACCEPTED_CLASSES: HashSet<String> = CLASSES.keys().cloned().collect();
+    // Deprecated classes are accepted with a warning, even once removed from the css
+    static ref DEPRECATIONS: Deprecations = {
+        let mut deprecations = deprecated_classes(&CLASSES);
 
-static CONFIG_FILE_NAME: &str = "pyaco.toml";
+        deprecations.extend(CONFIG.deprecations.clone());
 
-lazy_static! {
-    static ref CONFIG: Config = read_config().expect("couldn't read config file");
-    static ref ACCEPTED_CLASSES: HashSet<String> = match CONFIG.general.input {
-        InputConfig::Simple(ref path) | InputConfig::Path { ref path } =>
-            extract_classes_from_file(path),
-        InputConfig::Url { ref url } => extract_classes_from_url(url),
-    }
-    .expect("css could not be loaded");
+        deprecations
+    };
+    static ref ALIASES: Aliases = {
+        let aliases = CONFIG.aliases.clone();
+
+        validate_aliases(&aliases, |class| ACCEPTED_CLASSES.contains(class))
+            .expect("invalid aliases");
+
+        aliases
+    };
 }
 
 fn read_config() -> Result<Config> {
@@ -51,15 +64,7 @@ fn read_config() -> Result<Config> {
         ));
     }
 
-    let mut file = File::open(filename)?;
-
-    let mut content = String::new();
-
-    file.read_to_string(&mut content)?;
-
-    let config = toml::from_str(content.as_str())?;
-
-    Ok(config)
+    Config::read(filename)
 }
 
 #[proc_macro]
@@ -73,14 +78,24 @@ pub fn css(input: TokenStream) -> TokenStream {
 
     let mut out_classes = String::new();
 
+    // Aliases are expanded into their classes
+    let classes = classes
+        .into_iter()
+        .flat_map(|class| match ALIASES.get(class) {
+            Some(members) => members.iter().map(String::as_str).collect(),
+            None => vec![class],
+        });
+
     // Validate class names
     for class in classes {
-        if out_classes.contains(&class) {
+        if out_classes.contains(class) {
             emit_call_site_warning!("Class already in class names list: {}", class);
             continue;
         }
 
-        if !ACCEPTED_CLASSES.contains(&class.to_string()) {
+        if let Some(deprecation) = DEPRECATIONS.get(class) {
+            emit_call_site_warning!("Deprecated class name: {}", deprecation.describe(class));
+        } else if !ACCEPTED_CLASSES.contains(&class.to_string()) {
             abort_call_site!("Invalid class name: {}", class)
         }
 
@@ -2,8 +2,8 @@
 extern crate lazy_static;
 
 use neon::prelude::*;
-use pyaco_core::Lang;
-use pyaco_generate::{run as run_generate, Options as GenerateOptions};
+use pyaco_core::{ClassPattern, Framework, JsFormat, Lang, SplitBy, TypescriptStyle};
+use pyaco_generate::{run as run_generate, Generator, Options as GenerateOptions, Target};
 use pyaco_validate::{run as run_validate, Options as ValidateOptions};
 use tokio::runtime::Runtime;
 
@@ -21,15 +21,28 @@ fn generate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         .get::<JsString, FunctionContext, _>(&mut cx, "input")?
         .value(&mut cx);
 
-    let lang = options.get::<JsString, FunctionContext, _>(&mut cx, "lang");
+    let template = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "template")?
+        .map(|template| template.value(&mut cx));
+
+    let include = class_patterns(&mut cx, options, "include")?;
+
+    let exclude = class_patterns(&mut cx, options, "exclude")?;
+
+    let class_prefix = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "classPrefix")?
+        .map(|class_prefix| class_prefix.value(&mut cx));
+
+    let lang = options.get_opt::<JsString, FunctionContext, _>(&mut cx, "lang");
 
     let lang = match lang {
         Err(_) => return cx.throw_error("Invalid lang"),
-        Ok(lang) => {
+        Ok(None) => None,
+        Ok(Some(lang)) => {
             let lang = lang.value(&mut cx);
 
             match lang.parse::<Lang>() {
-                Ok(lang) => lang,
+                Ok(lang) => Some(lang),
                 Err(err) => return cx.throw_error(err),
             }
         }
@@ -40,19 +53,183 @@ fn generate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         .value(&mut cx);
 
     let output_filename = options
-        .get::<JsString, FunctionContext, _>(&mut cx, "outputFilename")?
-        .value(&mut cx);
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "outputFilename")?
+        .map(|output_filename| output_filename.value(&mut cx));
+
+    let targets = match options.get_opt::<JsArray, FunctionContext, _>(&mut cx, "targets")? {
+        None => Vec::new(),
+        Some(targets) => {
+            let targets = targets.to_vec(&mut cx)?;
+
+            let mut ret = Vec::with_capacity(targets.len());
+
+            for target in targets {
+                let target = target.downcast_or_throw::<JsObject, _>(&mut cx)?;
+
+                ret.push(generate_target(&mut cx, target)?);
+            }
+
+            ret
+        }
+    };
 
     let watch = options
         .get::<JsBoolean, FunctionContext, _>(&mut cx, "watch")?
         .value(&mut cx);
 
+    let source_root = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "sourceRoot")?
+        .map(|source_root| source_root.value(&mut cx));
+
+    let namespaces = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "namespaces")?
+        .is_some_and(|namespaces| namespaces.value(&mut cx));
+
+    let variants = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "variants")?
+        .is_some_and(|variants| variants.value(&mut cx));
+
+    let breakpoints = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "breakpoints")?
+        .is_some_and(|breakpoints| breakpoints.value(&mut cx));
+
+    let animations = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "animations")?
+        .is_some_and(|animations| animations.value(&mut cx));
+
+    let guards = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "guards")?
+        .is_some_and(|guards| guards.value(&mut cx));
+
+    let tokens = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "tokens")?
+        .is_some_and(|tokens| tokens.value(&mut cx));
+
+    let adapters = match options.get_opt::<JsArray, FunctionContext, _>(&mut cx, "adapters")? {
+        None => Vec::new(),
+        Some(adapters) => {
+            let adapters = adapters.to_vec(&mut cx)?;
+
+            let mut ret = Vec::with_capacity(adapters.len());
+
+            for adapter in adapters {
+                let adapter = adapter
+                    .downcast_or_throw::<JsString, _>(&mut cx)?
+                    .value(&mut cx);
+
+                match adapter.parse::<Framework>() {
+                    Ok(adapter) => ret.push(adapter),
+                    Err(err) => return cx.throw_error(err),
+                }
+            }
+
+            ret
+        }
+    };
+
+    let typescript_style =
+        match options.get_opt::<JsString, FunctionContext, _>(&mut cx, "typescriptStyle")? {
+            None => None,
+            Some(typescript_style) => {
+                let typescript_style = typescript_style.value(&mut cx);
+
+                match typescript_style.parse::<TypescriptStyle>() {
+                    Ok(typescript_style) => Some(typescript_style),
+                    Err(err) => return cx.throw_error(err),
+                }
+            }
+        };
+
+    let javascript = match options.get_opt::<JsString, FunctionContext, _>(&mut cx, "javascript")? {
+        None => None,
+        Some(javascript) => {
+            let javascript = javascript.value(&mut cx);
+
+            match javascript.parse::<JsFormat>() {
+                Ok(javascript) => Some(javascript),
+                Err(err) => return cx.throw_error(err),
+            }
+        }
+    };
+
+    let split = match options.get_opt::<JsString, FunctionContext, _>(&mut cx, "split")? {
+        None => None,
+        Some(split) => {
+            let split = split.value(&mut cx);
+
+            match split.parse::<SplitBy>() {
+                Ok(split) => Some(split),
+                Err(err) => return cx.throw_error(err),
+            }
+        }
+    };
+
+    let max_chunk_classes = options
+        .get_opt::<JsNumber, FunctionContext, _>(&mut cx, "maxChunkClasses")?
+        .map_or(1000, |max_chunk_classes| {
+            max_chunk_classes.value(&mut cx) as usize
+        });
+
+    let css_modules = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "cssModules")?
+        .is_some_and(|css_modules| css_modules.value(&mut cx));
+
+    let batch = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "batch")?
+        .is_some_and(|batch| batch.value(&mut cx));
+
+    let check = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "check")?
+        .is_some_and(|check| check.value(&mut cx));
+
+    let error_overlay = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "errorOverlay")?
+        .map(|error_overlay| error_overlay.value(&mut cx));
+
+    let manifest = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "manifest")?
+        .map(|manifest| manifest.value(&mut cx));
+
+    let json_schema = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "jsonSchema")?
+        .map(|json_schema| json_schema.value(&mut cx));
+
+    let tokens_json = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "tokensJson")?
+        .map(|tokens_json| tokens_json.value(&mut cx));
+
     let options = GenerateOptions {
         input,
+        include,
+        exclude,
         lang,
+        template,
         output_directory,
         output_filename,
+        targets,
+        source_root,
+        namespaces,
+        variants,
+        breakpoints,
+        animations,
+        guards,
+        tokens,
+        adapters,
+        class_prefix,
+        typescript_style,
+        javascript,
+        split,
+        max_chunk_classes,
+        css_modules,
+        batch,
         watch,
+        check,
+        error_overlay,
+        manifest,
+        json_schema,
+        tokens_json,
+        aliases: Default::default(),
+        deprecations: Default::default(),
     };
 
     match run_generate(options) {
@@ -61,6 +238,67 @@ fn generate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     }
 }
 
+/// Parses an optional array of globs and regexes
+fn class_patterns(
+    cx: &mut FunctionContext,
+    options: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<Vec<ClassPattern>> {
+    let patterns = match options.get_opt::<JsArray, FunctionContext, _>(cx, key)? {
+        None => return Ok(Vec::new()),
+        Some(patterns) => patterns.to_vec(cx)?,
+    };
+
+    let mut ret = Vec::with_capacity(patterns.len());
+
+    for pattern in patterns {
+        let pattern = pattern.downcast_or_throw::<JsString, _>(cx)?.value(cx);
+
+        match pattern.parse::<ClassPattern>() {
+            Ok(pattern) => ret.push(pattern),
+            Err(err) => return cx.throw_error(err),
+        }
+    }
+
+    Ok(ret)
+}
+
+fn generate_target(cx: &mut FunctionContext, target: Handle<JsObject>) -> NeonResult<Target> {
+    let template = target
+        .get_opt::<JsString, FunctionContext, _>(cx, "template")?
+        .map(|template| template.value(cx));
+
+    let generator = match (
+        target.get_opt::<JsString, FunctionContext, _>(cx, "lang")?,
+        template,
+    ) {
+        (Some(lang), _) => {
+            let lang = lang.value(cx);
+
+            match lang.parse::<Lang>() {
+                Ok(lang) => Generator::Lang(lang),
+                Err(err) => return cx.throw_error(err),
+            }
+        }
+        (None, Some(template)) => Generator::Template(template),
+        (None, None) => return cx.throw_error("Either a lang or a template must be provided"),
+    };
+
+    let output_directory = target
+        .get::<JsString, FunctionContext, _>(cx, "outputDirectory")?
+        .value(cx);
+
+    let output_filename = target
+        .get::<JsString, FunctionContext, _>(cx, "outputFilename")?
+        .value(cx);
+
+    Ok(Target {
+        generator,
+        output_directory,
+        output_filename,
+    })
+}
+
 fn validate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let options = cx.argument::<JsObject>(0)?;
 
@@ -78,6 +316,10 @@ fn validate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         .get::<JsString, FunctionContext, _>(&mut cx, "inputGlob")?
         .value(&mut cx);
 
+    let include = class_patterns(&mut cx, options, "include")?;
+
+    let exclude = class_patterns(&mut cx, options, "exclude")?;
+
     // The following will not panic, but the result is not reliable and might
     // change depending on the platform (32/64 bits).
     // Since we don't expect big numbers to be provided it should work fine though.
@@ -89,12 +331,27 @@ fn validate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         .get::<JsString, FunctionContext, _>(&mut cx, "splitRegex")?
         .value(&mut cx);
 
+    let animations = options
+        .get_opt::<JsBoolean, FunctionContext, _>(&mut cx, "animations")?
+        .is_some_and(|animations| animations.value(&mut cx));
+
+    let style_regex = options
+        .get_opt::<JsString, FunctionContext, _>(&mut cx, "styleRegex")?
+        .map_or_else(
+            || r#"style="([^"]+)""#.to_string(),
+            |style_regex| style_regex.value(&mut cx),
+        );
+
     let options = ValidateOptions {
         capture_regex,
         css_input,
         input_glob,
+        include,
+        exclude,
         max_opened_files,
         split_regex,
+        animations,
+        style_regex,
     };
 
     let ret = cx.undefined();
@@ -6,8 +6,12 @@ use grep_matcher::{Captures, Matcher};
 use grep_regex::RegexMatcher;
 use grep_searcher::{sinks::UTF8, SearcherBuilder};
 use log::{error, info};
-use pyaco_core::InputType;
+use pyaco_core::{
+    animation_names, deprecated_classes, validate_aliases, ClassPattern, Config, InputType,
+    CONFIG_FILE_NAME,
+};
 use regex::Regex;
+use std::io::{Seek, SeekFrom};
 use std::{borrow::Borrow, collections::HashSet, fs::File, path::Path, process::exit, sync::Arc};
 use tokio::sync::Mutex;
 
@@ -21,6 +25,14 @@ pub struct Options {
     #[clap(short, long)]
     pub input_glob: String,
 
+    /// Only accept the classes of the css input matching this glob (`p-*`), or regex written between slashes (`/^bg-/`), can be repeated, defaults to the `include` patterns of the `[filter]` section of pyaco.toml
+    #[clap(long = "include", multiple_occurrences = true)]
+    pub include: Vec<ClassPattern>,
+
+    /// Reject the classes of the css input matching this glob (`float-*`), or regex written between slashes (`/-(amber|lime)-/`), can be repeated, defaults to the `exclude` patterns of the `[filter]` section of pyaco.toml
+    #[clap(long = "exclude", multiple_occurrences = true)]
+    pub exclude: Vec<ClassPattern>,
+
     /// Classes matcher regex, must include a capture containing all the classes
     #[clap(long, default_value = r#"class="([^"]+)""#)]
     pub capture_regex: String,
@@ -32,6 +44,21 @@ pub struct Options {
     /// How many files can be read concurrently at most, setting this value to a big number might break depending on your system
     #[clap(long, default_value = "128")]
     pub max_opened_files: usize,
+
+    /// Also check the animation names used in inline styles (`animation`, `animation-name`) against the `@keyframes` of the css input
+    #[clap(long)]
+    pub animations: bool,
+
+    /// Inline styles matcher regex, must include a capture containing the declarations, used with --animations
+    #[clap(long, default_value = r#"style="([^"]+)""#)]
+    pub style_regex: String,
+}
+
+/// What was found in a validated file
+#[derive(Default)]
+pub struct FoundNames {
+    pub classes: HashSet<String>,
+    pub animation_names: HashSet<String>,
 }
 
 pub async fn run(options: Options) -> Result<()> {
@@ -39,6 +66,12 @@ pub async fn run(options: Options) -> Result<()> {
 
     let split_regex = Arc::new(Regex::new(options.split_regex.as_str())?);
 
+    let style_regex = if options.animations {
+        Some(Arc::new(RegexMatcher::new(options.style_regex.as_str())?))
+    } else {
+        None
+    };
+
     info!(
         "Validating {} against {}",
         options.input_glob, options.css_input
@@ -46,8 +79,31 @@ pub async fn run(options: Options) -> Result<()> {
 
     let css_input = InputType::from_path(options.css_input);
 
-    // The classes contained in the provided css file/URL
-    let accepted_classes = css_input.extract_classes()?;
+    let config = Config::read(CONFIG_FILE_NAME)?;
+
+    let filter = config.filter(options.include, options.exclude);
+
+    // The filtered out classes are reported as unknown, as if they weren't in the css
+    let stylesheet = css_input.extract_filtered_stylesheet(&filter)?;
+
+    // Deprecated classes are reported without failing the validation, including the ones
+    // declared in the configuration that were already removed from the css
+    let mut deprecations = deprecated_classes(&stylesheet.classes);
+
+    deprecations.extend(config.deprecations);
+
+    // The classes contained in the provided css file/URL, and accepted by the filter
+    let mut accepted_classes = stylesheet.classes.into_keys().collect::<HashSet<_>>();
+
+    let aliases = config.aliases;
+
+    validate_aliases(&aliases, |class| accepted_classes.contains(class))?;
+
+    // The aliases of the configuration file are accepted as classes
+    accepted_classes.extend(aliases.into_keys());
+
+    // The `@keyframes` names contained in the provided css file/URL
+    let accepted_animation_names = stylesheet.animations.into_keys().collect::<HashSet<_>>();
 
     let glob = glob(options.input_glob.as_str())?;
 
@@ -64,29 +120,44 @@ pub async fn run(options: Options) -> Result<()> {
 
             let capture_regex = capture_regex.clone();
 
-            tokio::spawn(extra_classes_from_path(file, capture_regex, split_regex))
+            let style_regex = style_regex.clone();
+
+            tokio::spawn(extract_names_from_path(
+                file,
+                capture_regex,
+                split_regex,
+                style_regex,
+            ))
         })
         .buffer_unordered(options.max_opened_files);
 
-    let found_classes = Mutex::new(HashSet::new());
+    let found = Mutex::new(FoundNames::default());
 
-    // Insert the classes captured into the `found_classes` set
+    // Insert the classes and animation names captured into the `found` sets
     jobs.for_each(|job| async {
-        let mut found_classes = found_classes.lock().await;
+        let mut found = found.lock().await;
 
-        if let Ok(Ok(classes)) = job {
-            for class in classes {
-                found_classes.insert(class);
-            }
+        if let Ok(Ok(names)) = job {
+            found.classes.extend(names.classes);
+            found.animation_names.extend(names.animation_names);
         }
     })
     .await;
 
-    let found_classes = found_classes.lock().await;
+    let found = found.lock().await;
+
+    let found_classes = &found.classes;
+
+    for (class, deprecation) in deprecations.iter() {
+        if found_classes.contains(class) {
+            eprintln!("Warning: {}", deprecation.describe(class));
+        }
+    }
 
     // Diff between whitelisted classes found the provided css and the classes found in the files
     let unknown_classes = found_classes
         .difference(&accepted_classes)
+        .filter(|class| !deprecations.contains_key(*class))
         .collect::<HashSet<&String>>();
 
     info!(
@@ -95,11 +166,28 @@ pub async fn run(options: Options) -> Result<()> {
         accepted_classes.len()
     );
 
-    if !unknown_classes.is_empty() {
+    let unknown_animation_names = found
+        .animation_names
+        .difference(&accepted_animation_names)
+        .collect::<HashSet<&String>>();
+
+    if options.animations {
+        info!(
+            "{} animation names used in total in the provided files, {} whitelisted animation names",
+            found.animation_names.len(),
+            accepted_animation_names.len()
+        );
+    }
+
+    if !unknown_classes.is_empty() || !unknown_animation_names.is_empty() {
         for class in unknown_classes {
             eprintln!("Unknown class found {}", class);
         }
 
+        for animation_name in unknown_animation_names {
+            eprintln!("Unknown animation name found {}", animation_name);
+        }
+
         exit(1);
     }
 
@@ -108,12 +196,13 @@ pub async fn run(options: Options) -> Result<()> {
     Ok(())
 }
 
-pub async fn extra_classes_from_path<C, S>(
-    file: File,
+pub async fn extra_classes_from_path<F, C, S>(
+    file: F,
     capture_regex: C,
     split_regex: S,
 ) -> Result<HashSet<String>>
 where
+    F: Borrow<File>,
     C: Borrow<RegexMatcher>,
     S: Borrow<Regex>,
 {
@@ -127,7 +216,7 @@ where
 
     searcher.search_file(
         capture_regex,
-        &file,
+        file.borrow(),
         UTF8(|_, line| {
             let mut captures = capture_regex.new_captures()?;
 
@@ -152,6 +241,78 @@ where
     Ok(found_classes)
 }
 
+/// Extracts the classes, and the animation names used in inline styles when a style regex is provided
+pub async fn extract_names_from_path<C, S>(
+    mut file: File,
+    capture_regex: C,
+    split_regex: S,
+    style_regex: Option<C>,
+) -> Result<FoundNames>
+where
+    C: Borrow<RegexMatcher>,
+    S: Borrow<Regex>,
+{
+    let classes = extra_classes_from_path(&file, capture_regex, split_regex).await?;
+
+    let animation_names = match style_regex {
+        Some(style_regex) => {
+            file.seek(SeekFrom::Start(0))?;
+
+            extract_animation_names_from_path(&file, style_regex).await?
+        }
+        None => HashSet::new(),
+    };
+
+    Ok(FoundNames {
+        classes,
+        animation_names,
+    })
+}
+
+/// Extracts the animation names referred to by the `animation` and `animation-name`
+/// declarations of the inline styles captured with the style regex
+pub async fn extract_animation_names_from_path<F, C>(
+    file: F,
+    style_regex: C,
+) -> Result<HashSet<String>>
+where
+    F: Borrow<File>,
+    C: Borrow<RegexMatcher>,
+{
+    let mut found_animation_names = HashSet::new();
+
+    let mut searcher = SearcherBuilder::new().multi_line(true).build();
+
+    let style_regex = style_regex.borrow();
+
+    searcher.search_file(
+        style_regex,
+        file.borrow(),
+        UTF8(|_, line| {
+            let mut captures = style_regex.new_captures()?;
+
+            // The matched lines can contain several inline styles
+            style_regex.captures_iter(line.as_bytes(), &mut captures, |captures| {
+                if let Some(m) = captures.get(1) {
+                    // Inline styles are a list of declarations
+                    for declaration in line[m].split(';') {
+                        if let Some((property, value)) = declaration.split_once(':') {
+                            found_animation_names
+                                .extend(animation_names(property.trim(), value.trim()));
+                        }
+                    }
+                }
+
+                true
+            })?;
+
+            Ok(true)
+        }),
+    )?;
+
+    Ok(found_animation_names)
+}
+
 pub async fn open_file<P: AsRef<Path>>(path: P) -> Option<File> {
     match File::open(path.as_ref()) {
         Ok(file) => Some(file),
@@ -10,6 +10,8 @@ pub struct Options {
     pub command: Command,
 }
 
+// Parsed once at startup, boxing the options isn't worth it
+#[allow(clippy::large_enum_variant)]
 #[derive(Clap, Debug)]
 pub enum Command {
     /// Generate code from a css input